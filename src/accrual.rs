@@ -0,0 +1,644 @@
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow, bail};
+use chrono::{Datelike, Days, NaiveDate};
+use csv::{ReaderBuilder, Trim};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::Deserialize;
+
+use crate::sources::compounding::DayCount;
+use crate::sources::de::de_date;
+
+/// Day-count basis the AQA rate is annualized on (see `adjust_basis`)
+const DAY_COUNT: DayCount = DayCount::Act36525;
+
+/// Scaled rate denominator: 1% = 1_000_000, so 100% = 100_000_000
+const RATE_SCALE: Decimal = Decimal::from_parts(100_000_000, 0, 0, false, 0);
+
+/// Reporting period used to group daily accruals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Period {
+    /// Calendar month
+    #[default]
+    Month,
+    /// ISO week (Monday to Sunday)
+    Week,
+    /// Entire requested range
+    Whole,
+}
+
+impl Period {
+    /// First day of the period containing `date`, clamped to the range start
+    fn start_of(self, date: NaiveDate, range_start: NaiveDate) -> NaiveDate {
+        let start = match self {
+            Period::Month => date.with_day(1).unwrap(),
+            Period::Week => date
+                .checked_sub_days(Days::new(date.weekday().num_days_from_monday() as u64))
+                .unwrap(),
+            Period::Whole => range_start,
+        };
+        start.max(range_start)
+    }
+}
+
+impl FromStr for Period {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "month" => Ok(Period::Month),
+            "week" => Ok(Period::Week),
+            "whole" => Ok(Period::Whole),
+            _ => bail!("unknown period '{s}' (expected `month`, `week` or `whole`)"),
+        }
+    }
+}
+
+/// Income accrued on a single calendar day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyAccrual {
+    pub date: NaiveDate,
+    /// AQA rate applied (scaled, 1% = 1_000_000)
+    pub rate: u64,
+    /// Date of the AQA rate applied (earlier than `date` if carried forward)
+    pub rate_date: NaiveDate,
+    /// Reserve balance applied
+    pub balance: Decimal,
+    /// Date of the balance applied (earlier than `date` if carried forward)
+    pub balance_date: NaiveDate,
+    /// Unrounded accrued amount: balance × rate / 365.25
+    pub amount: Decimal,
+}
+
+/// Income accrued over a reporting period, with reconciliation figures
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeriodAccrual {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// Number of calendar days accrued
+    pub days: u64,
+    /// Unrounded period total, computed from Σ(balance × rate) with a single division
+    pub total: Decimal,
+    /// `total` rounded down to the reporting precision (payor-friendly)
+    pub total_rounded: Decimal,
+    /// Sum of daily amounts each rounded down to the reporting precision
+    pub sum_of_rounded_days: Decimal,
+}
+
+impl PeriodAccrual {
+    /// Difference between the rounded period total and the sum of rounded days
+    pub fn rounding_residual(&self) -> Decimal {
+        self.total_rounded - self.sum_of_rounded_days
+    }
+}
+
+/// Full accrual report over a date range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccrualReport {
+    pub days: Vec<DailyAccrual>,
+    pub periods: Vec<PeriodAccrual>,
+    /// Unrounded total owed over the whole range
+    pub total: Decimal,
+    /// Decimal places amounts are rounded to for reporting
+    pub decimal_places: u32,
+}
+
+impl AccrualReport {
+    /// Days whose AQA rate was carried forward from an earlier date
+    pub fn carried_rates(&self) -> impl Iterator<Item = &DailyAccrual> {
+        self.days.iter().filter(|d| d.rate_date != d.date)
+    }
+
+    /// Days whose balance was carried forward from an earlier date
+    pub fn carried_balances(&self) -> impl Iterator<Item = &DailyAccrual> {
+        self.days.iter().filter(|d| d.balance_date != d.date)
+    }
+
+    /// Reconcile the report against the `rates` and `balances` it was accrued from
+    ///
+    /// Each period's unrounded accrual is recomputed directly from the input series as
+    /// Σ(balance × rate) / 365.25 and compared with the period total, the sum of its daily
+    /// amounts and its rounded figures; the range total is compared with the sum of the
+    /// recomputed periods. Fails listing every mismatch.
+    pub fn reconcile(
+        &self,
+        rates: &BTreeMap<NaiveDate, u64>,
+        balances: &BTreeMap<NaiveDate, Decimal>,
+    ) -> Result<()> {
+        let tolerance = Decimal::new(1, self.decimal_places);
+        let mut mismatches = Vec::new();
+        let mut expected_total = Decimal::ZERO;
+        for p in &self.periods {
+            let expected = period_accrual(rates, balances, p.start, p.end)?;
+            expected_total = checked_add(expected_total, expected)?;
+
+            let label = format!("{} to {}", p.start, p.end);
+            let days = self
+                .days
+                .iter()
+                .filter(|d| (p.start..=p.end).contains(&d.date))
+                .try_fold(Decimal::ZERO, |sum, d| checked_add(sum, d.amount))?;
+            if (p.total - expected).abs() >= tolerance {
+                mismatches.push(format!("{label}: total {} != {expected}", p.total));
+            }
+            if (days - expected).abs() >= tolerance {
+                mismatches.push(format!(
+                    "{label}: daily amounts sum to {days}, not {expected}"
+                ));
+            }
+            if p.total_rounded != floor_dp(expected, self.decimal_places) {
+                mismatches.push(format!(
+                    "{label}: rounded total {} != {}",
+                    p.total_rounded,
+                    floor_dp(expected, self.decimal_places)
+                ));
+            }
+            // Flooring each day loses less than one unit per day
+            let residual = p.rounding_residual();
+            if residual.is_sign_negative() || residual >= tolerance * Decimal::from(p.days) {
+                mismatches.push(format!(
+                    "{label}: rounding residual {residual} out of range for {} day(s)",
+                    p.days
+                ));
+            }
+        }
+        if (self.total - expected_total).abs() >= tolerance {
+            mismatches.push(format!("total {} != {expected_total}", self.total));
+        }
+
+        if !mismatches.is_empty() {
+            bail!(
+                "accrual report does not reconcile:\n{}",
+                mismatches.join("\n")
+            )
+        }
+        Ok(())
+    }
+}
+
+/// Unrounded accrual over [start, end] straight from the input series: Σ(balance × rate) / 365.25
+fn period_accrual(
+    rates: &BTreeMap<NaiveDate, u64>,
+    balances: &BTreeMap<NaiveDate, Decimal>,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Decimal> {
+    let mut weighted = Decimal::ZERO;
+    for date in start.iter_days().take_while(|d| *d <= end) {
+        let (_, rate) =
+            on_or_before(rates, date).ok_or_else(|| anyhow!("no AQA rate on or before {date}"))?;
+        let (_, balance) = on_or_before(balances, date)
+            .ok_or_else(|| anyhow!("no reserve balance on or before {date}"))?;
+        weighted = checked_add(weighted, weight(balance, rate)?)?;
+    }
+    annualize(weighted)
+}
+
+/// Balance × scaled rate for one day, failing on overflow
+fn weight(balance: Decimal, rate: u64) -> Result<Decimal> {
+    balance
+        .checked_mul(Decimal::from(rate))
+        .ok_or_else(|| anyhow!("accrual overflow: balance {balance} × rate {rate}"))
+}
+
+/// Sum of two weights or amounts, failing on overflow
+fn checked_add(a: Decimal, b: Decimal) -> Result<Decimal> {
+    a.checked_add(b)
+        .ok_or_else(|| anyhow!("accrual overflow adding {a} and {b}"))
+}
+
+/// Amount accrued on Σ(balance × scaled rate) over ACT/365.25
+///
+/// Dividing by 365.25 is generally inexact: the quotient is rounded to the 28 significant
+/// digits a `Decimal` holds, far below any reporting precision.
+fn annualize(weighted: Decimal) -> Result<Decimal> {
+    weighted
+        .checked_div(RATE_SCALE)
+        .and_then(|w| w.checked_div(DAY_COUNT.denominator()))
+        .ok_or_else(|| anyhow!("accrual overflow dividing {weighted}"))
+}
+
+/// Round an amount down to `dp` decimal places (payor-friendly)
+fn floor_dp(amount: Decimal, dp: u32) -> Decimal {
+    amount.round_dp_with_strategy(dp, RoundingStrategy::ToZero)
+}
+
+/// Latest (date, value) on or before `date`
+fn on_or_before<T: Copy>(
+    series: &BTreeMap<NaiveDate, T>,
+    date: NaiveDate,
+) -> Option<(NaiveDate, T)> {
+    series.range(..=date).next_back().map(|(d, v)| (*d, *v))
+}
+
+/// Accrue reserve income owed for every calendar day in [start, end]
+///
+/// Each day accrues `balance × rate / 365.25`, where `rate` is the annualized AQA
+/// rate (scaled, 1% = 1_000_000) on an ACT/365.25 basis. Days without a published
+/// rate or balance carry forward the latest earlier value. All arithmetic is `Decimal`
+/// and fails rather than overflow; amounts carry 28 significant digits (see `annualize`)
+/// and only reported figures are rounded down to `decimal_places`.
+pub fn accrue(
+    rates: &BTreeMap<NaiveDate, u64>,
+    balances: &BTreeMap<NaiveDate, Decimal>,
+    start: NaiveDate,
+    end: NaiveDate,
+    period: Period,
+    decimal_places: u32,
+) -> Result<AccrualReport> {
+    if end < start {
+        bail!("end date {end} is before start date {start}")
+    }
+
+    // Accrue every calendar day
+    let mut days = Vec::new();
+    let mut date = start;
+    while date <= end {
+        let (rate_date, rate) =
+            on_or_before(rates, date).ok_or_else(|| anyhow!("no AQA rate on or before {date}"))?;
+        let (balance_date, balance) = on_or_before(balances, date)
+            .ok_or_else(|| anyhow!("no reserve balance on or before {date}"))?;
+        if balance.is_sign_negative() {
+            bail!("negative reserve balance on {balance_date}: {balance}")
+        }
+
+        days.push(DailyAccrual {
+            date,
+            rate,
+            rate_date,
+            balance,
+            balance_date,
+            amount: annualize(weight(balance, rate)?)?,
+        });
+        date = date
+            .checked_add_days(Days::new(1))
+            .ok_or_else(|| anyhow!("date overflow"))?;
+    }
+
+    // Group days into periods, computing each total independently of daily amounts
+    let mut groups: BTreeMap<NaiveDate, Vec<&DailyAccrual>> = BTreeMap::new();
+    for day in &days {
+        groups
+            .entry(period.start_of(day.date, start))
+            .or_default()
+            .push(day);
+    }
+    let periods = groups
+        .into_values()
+        .map(|group| {
+            let mut weighted = Decimal::ZERO;
+            let mut sum_of_rounded_days = Decimal::ZERO;
+            for d in &group {
+                weighted = checked_add(weighted, weight(d.balance, d.rate)?)?;
+                sum_of_rounded_days =
+                    checked_add(sum_of_rounded_days, floor_dp(d.amount, decimal_places))?;
+            }
+            let total = annualize(weighted)?;
+            Ok(PeriodAccrual {
+                start: group[0].date,
+                end: group[group.len() - 1].date,
+                days: group.len() as u64,
+                total,
+                total_rounded: floor_dp(total, decimal_places),
+                sum_of_rounded_days,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let total = periods
+        .iter()
+        .try_fold(Decimal::ZERO, |sum, p| checked_add(sum, p.total))?;
+    Ok(AccrualReport {
+        days,
+        periods,
+        total,
+        decimal_places,
+    })
+}
+
+/// Row of a daily AQA rate history CSV (`date,rate`, rate scaled with 1% = 1_000_000)
+#[derive(Debug, Deserialize)]
+struct RateRow {
+    #[serde(deserialize_with = "de_date")]
+    date: NaiveDate,
+    rate: u64,
+}
+
+/// Row of a daily reserve balance CSV (`date,balance`)
+#[derive(Debug, Deserialize)]
+struct BalanceRow {
+    #[serde(deserialize_with = "de_date")]
+    date: NaiveDate,
+    balance: String,
+}
+
+/// Read a daily AQA rate history CSV with `date,rate` header
+pub fn read_rates_csv<R: Read>(reader: R) -> Result<BTreeMap<NaiveDate, u64>> {
+    let mut reader = ReaderBuilder::new().trim(Trim::All).from_reader(reader);
+    let mut rates = BTreeMap::new();
+    for row in reader.deserialize::<RateRow>() {
+        let row = row?;
+        if rates.insert(row.date, row.rate).is_some() {
+            bail!("duplicate AQA rate for {}", row.date)
+        }
+    }
+    Ok(rates)
+}
+
+/// Read a daily reserve balance CSV with `date,balance` header
+pub fn read_balances_csv<R: Read>(reader: R) -> Result<BTreeMap<NaiveDate, Decimal>> {
+    let mut reader = ReaderBuilder::new().trim(Trim::All).from_reader(reader);
+    let mut balances = BTreeMap::new();
+    for row in reader.deserialize::<BalanceRow>() {
+        let row = row?;
+        let balance = Decimal::from_str(row.balance.trim())
+            .map_err(|e| anyhow!("invalid balance on {}: {e}", row.date))?;
+        if balances.insert(row.date, balance).is_some() {
+            bail!("duplicate reserve balance for {}", row.date)
+        }
+    }
+    Ok(balances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    mod accrue_tests {
+        use super::*;
+
+        #[test]
+        fn single_day_act_365_25() {
+            // 1,000,000 at 3.6525% for one day = 100.00 exactly
+            let rates = BTreeMap::from([(date(2025, 1, 1), 3_652_500)]);
+            let balances = BTreeMap::from([(date(2025, 1, 1), dec!(1000000))]);
+            let report = accrue(
+                &rates,
+                &balances,
+                date(2025, 1, 1),
+                date(2025, 1, 1),
+                Period::Whole,
+                2,
+            )
+            .unwrap();
+
+            assert_eq!(report.days.len(), 1);
+            assert_eq!(report.days[0].amount, dec!(100));
+            assert_eq!(report.total, dec!(100));
+            report.reconcile(&rates, &balances).unwrap();
+        }
+
+        #[test]
+        fn carries_forward_rates_and_balances() {
+            let rates = BTreeMap::from([(date(2025, 1, 3), 4_000_000)]);
+            let balances =
+                BTreeMap::from([(date(2025, 1, 1), dec!(500)), (date(2025, 1, 5), dec!(700))]);
+            let report = accrue(
+                &rates,
+                &balances,
+                date(2025, 1, 3),
+                date(2025, 1, 6),
+                Period::Whole,
+                2,
+            )
+            .unwrap();
+
+            assert_eq!(report.carried_rates().count(), 3);
+            let carried: Vec<_> = report.carried_balances().map(|d| d.date).collect();
+            assert_eq!(
+                carried,
+                vec![date(2025, 1, 3), date(2025, 1, 4), date(2025, 1, 6)]
+            );
+            assert_eq!(report.days[3].balance, dec!(700));
+        }
+
+        #[test]
+        fn groups_by_month() {
+            let rates = BTreeMap::from([(date(2025, 1, 1), 4_000_000)]);
+            let balances = BTreeMap::from([(date(2025, 1, 1), dec!(1234567.89))]);
+            let report = accrue(
+                &rates,
+                &balances,
+                date(2025, 1, 15),
+                date(2025, 3, 10),
+                Period::Month,
+                2,
+            )
+            .unwrap();
+
+            let bounds: Vec<_> = report
+                .periods
+                .iter()
+                .map(|p| (p.start, p.end, p.days))
+                .collect();
+            assert_eq!(
+                bounds,
+                vec![
+                    (date(2025, 1, 15), date(2025, 1, 31), 17),
+                    (date(2025, 2, 1), date(2025, 2, 28), 28),
+                    (date(2025, 3, 1), date(2025, 3, 10), 10),
+                ]
+            );
+            report.reconcile(&rates, &balances).unwrap();
+        }
+
+        #[test]
+        fn groups_by_week() {
+            let rates = BTreeMap::from([(date(2025, 1, 1), 4_000_000)]);
+            let balances = BTreeMap::from([(date(2025, 1, 1), dec!(100))]);
+            // 2025-01-01 is a Wednesday
+            let report = accrue(
+                &rates,
+                &balances,
+                date(2025, 1, 1),
+                date(2025, 1, 13),
+                Period::Week,
+                2,
+            )
+            .unwrap();
+
+            let starts: Vec<_> = report.periods.iter().map(|p| p.start).collect();
+            assert_eq!(
+                starts,
+                vec![date(2025, 1, 1), date(2025, 1, 6), date(2025, 1, 13)]
+            );
+        }
+
+        #[test]
+        fn rounding_residual_is_reported() {
+            // Each day floors to 0.01 but the period total is 0.03
+            let rates = BTreeMap::from([(date(2025, 1, 1), 3_652_500)]);
+            let balances = BTreeMap::from([(date(2025, 1, 1), dec!(150))]);
+            let report = accrue(
+                &rates,
+                &balances,
+                date(2025, 1, 1),
+                date(2025, 1, 2),
+                Period::Whole,
+                2,
+            )
+            .unwrap();
+
+            let period = &report.periods[0];
+            assert_eq!(report.days[0].amount, dec!(0.015));
+            assert_eq!(period.total_rounded, dec!(0.03));
+            assert_eq!(period.sum_of_rounded_days, dec!(0.02));
+            assert_eq!(period.rounding_residual(), dec!(0.01));
+            report.reconcile(&rates, &balances).unwrap();
+        }
+
+        #[test]
+        fn reconciliation_catches_mismatches() {
+            let rates = BTreeMap::from([(date(2025, 1, 1), 4_000_000)]);
+            let balances = BTreeMap::from([(date(2025, 1, 1), dec!(1000000))]);
+            let report = accrue(
+                &rates,
+                &balances,
+                date(2025, 1, 1),
+                date(2025, 2, 28),
+                Period::Month,
+                2,
+            )
+            .unwrap();
+            report.reconcile(&rates, &balances).unwrap();
+
+            // Balance corrected after the report was produced
+            let corrected = BTreeMap::from([
+                (date(2025, 1, 1), dec!(1000000)),
+                (date(2025, 2, 10), dec!(0)),
+            ]);
+            let err = report.reconcile(&rates, &corrected).unwrap_err();
+            assert!(
+                err.to_string().contains("2025-02-01 to 2025-02-28: total"),
+                "{err}"
+            );
+
+            // Daily amount edited without updating the period figures
+            let mut edited = report.clone();
+            edited.days[3].amount += dec!(0.05);
+            let err = edited.reconcile(&rates, &balances).unwrap_err();
+            assert!(err.to_string().contains("daily amounts sum to"), "{err}");
+
+            // Rounded figures inconsistent with the unrounded total
+            let mut edited = report.clone();
+            edited.periods[0].sum_of_rounded_days -= dec!(1);
+            let err = edited.reconcile(&rates, &balances).unwrap_err();
+            assert!(err.to_string().contains("rounding residual"), "{err}");
+        }
+
+        #[test]
+        fn matches_hand_computed_figures() {
+            // 1,000,000 at 4% accrues 40,000 / 365.25 a day until the balance drops to zero
+            let rates = BTreeMap::from([(date(2025, 1, 1), 4_000_000)]);
+            let balances = BTreeMap::from([
+                (date(2025, 1, 1), dec!(1000000)),
+                (date(2025, 2, 10), dec!(0)),
+            ]);
+            let report = accrue(
+                &rates,
+                &balances,
+                date(2025, 1, 1),
+                date(2025, 2, 28),
+                Period::Month,
+                2,
+            )
+            .unwrap();
+
+            let close = |actual: Decimal, expected: Decimal| {
+                assert!(
+                    (actual - expected).abs() < dec!(1e-20),
+                    "{actual} != {expected}"
+                )
+            };
+            close(report.days[0].amount, dec!(109.5140314852840520191649555));
+            // 31 × 40,000 / 365.25 and 9 × 40,000 / 365.25
+            close(report.periods[0].total, dec!(3394.934976043805612594113621));
+            close(report.periods[1].total, dec!(985.6262833675564681724845996));
+            close(report.total, dec!(4380.561259411362080766598220));
+            assert_eq!(report.periods[0].total_rounded, dec!(3394.93));
+            assert_eq!(report.periods[1].total_rounded, dec!(985.62));
+            // 31 × 109.51 and 9 × 109.51
+            assert_eq!(report.periods[0].sum_of_rounded_days, dec!(3394.81));
+            assert_eq!(report.periods[1].sum_of_rounded_days, dec!(985.59));
+            report.reconcile(&rates, &balances).unwrap();
+        }
+
+        #[test]
+        fn rejects_overflow() {
+            let rates = BTreeMap::from([(date(2025, 1, 1), 4_000_000)]);
+            let balances = BTreeMap::from([(date(2025, 1, 1), Decimal::MAX)]);
+            let err = accrue(
+                &rates,
+                &balances,
+                date(2025, 1, 1),
+                date(2025, 1, 1),
+                Period::Whole,
+                2,
+            )
+            .unwrap_err();
+            assert!(err.to_string().contains("overflow"), "{err}");
+        }
+
+        #[test]
+        fn rejects_missing_history() {
+            let rates = BTreeMap::from([(date(2025, 1, 5), 4_000_000)]);
+            let balances = BTreeMap::from([(date(2025, 1, 1), dec!(100))]);
+            let err = accrue(
+                &rates,
+                &balances,
+                date(2025, 1, 1),
+                date(2025, 1, 6),
+                Period::Whole,
+                2,
+            )
+            .unwrap_err();
+            assert!(err.to_string().contains("no AQA rate"));
+        }
+
+        #[test]
+        fn rejects_inverted_range() {
+            assert!(
+                accrue(
+                    &BTreeMap::new(),
+                    &BTreeMap::new(),
+                    date(2025, 1, 2),
+                    date(2025, 1, 1),
+                    Period::Whole,
+                    2,
+                )
+                .is_err()
+            );
+        }
+    }
+
+    mod csv_tests {
+        use super::*;
+
+        #[test]
+        fn reads_rates_and_balances() {
+            let rates =
+                read_rates_csv("date,rate\n2025-01-01,3515319\n2025-01-02, 3515400\n".as_bytes())
+                    .unwrap();
+            assert_eq!(rates[&date(2025, 1, 2)], 3_515_400);
+
+            let balances =
+                read_balances_csv("date,balance\n2025-01-01,1000000.50\n".as_bytes()).unwrap();
+            assert_eq!(balances[&date(2025, 1, 1)], dec!(1000000.50));
+        }
+
+        #[test]
+        fn rejects_duplicates() {
+            assert!(read_rates_csv("date,rate\n2025-01-01,1\n2025-01-01,2\n".as_bytes()).is_err());
+            assert!(
+                read_balances_csv("date,balance\n2025-01-01,1\n2025-01-01,2\n".as_bytes()).is_err()
+            );
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use anyhow::{Context, Result, bail};
+use chrono::{Days, NaiveDate};
+use log::{info, warn};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::File;
+
+use aqa_publisher::{
+    accrual::{Period, accrue, read_balances_csv, read_rates_csv},
+    formula::FormulaSet,
+    get_aqa_ref_rate,
+    sources::parse_ymd,
+};
+
+const USAGE: &str = "Usage: accrue --balances <balances.csv> (--rates <rates.csv> | --recompute) \
+--from <YYYY-MM-DD> --to <YYYY-MM-DD> [--period month|week|whole] [--decimals <n>]";
+
+/// Parsed command line arguments
+struct Args {
+    balances: String,
+    /// Rate history CSV; recomputed from live sources when absent
+    rates: Option<String>,
+    from: NaiveDate,
+    to: NaiveDate,
+    period: Period,
+    decimals: u32,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut balances = None;
+    let mut rates = None;
+    let mut recompute = false;
+    let mut from = None;
+    let mut to = None;
+    let mut period = Period::default();
+    let mut decimals = 2;
+
+    let mut args = env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .with_context(|| format!("{flag} requires a value"))
+        };
+        match flag.as_str() {
+            "--balances" => balances = Some(value()?),
+            "--rates" => rates = Some(value()?),
+            "--recompute" => recompute = true,
+            "--from" => from = Some(parse_ymd(&value()?)?),
+            "--to" => to = Some(parse_ymd(&value()?)?),
+            "--period" => period = value()?.parse()?,
+            "--decimals" => decimals = value()?.parse().context("invalid --decimals")?,
+            _ => bail!("unknown argument '{flag}'\n{USAGE}"),
+        }
+    }
+
+    if rates.is_some() == recompute {
+        bail!("exactly one of --rates or --recompute is required\n{USAGE}");
+    }
+    Ok(Args {
+        balances: balances.with_context(|| format!("--balances is required\n{USAGE}"))?,
+        rates,
+        from: from.with_context(|| format!("--from is required\n{USAGE}"))?,
+        to: to.with_context(|| format!("--to is required\n{USAGE}"))?,
+        period,
+        decimals,
+    })
+}
+
+/// Recompute the AQA rate for every day in [from, to] from live sources
+fn recompute_rates(from: NaiveDate, to: NaiveDate) -> Result<BTreeMap<NaiveDate, u64>> {
+    let formulas = FormulaSet::from_env()?;
+    let mut rates = BTreeMap::new();
+    let mut date = from;
+    while date <= to {
+        let (median_date, _, aqa_ref_rate) = get_aqa_ref_rate(date, &formulas)
+            .with_context(|| format!("Failed to recompute {date}"))?;
+        info!("Recomputed AQA rate on {date} (source date {median_date}): {aqa_ref_rate}");
+        rates.insert(date, aqa_ref_rate);
+        date = date.checked_add_days(Days::new(1)).unwrap();
+    }
+    Ok(rates)
+}
+
+fn main() -> Result<()> {
+    // Load environment variables
+    let _ = dotenvy::dotenv();
+    env_logger::init();
+
+    let args = parse_args()?;
+
+    // Load inputs
+    let balances = read_balances_csv(
+        File::open(&args.balances).with_context(|| format!("opening {}", args.balances))?,
+    )?;
+    let rates = match &args.rates {
+        Some(path) => read_rates_csv(File::open(path).with_context(|| format!("opening {path}"))?)?,
+        None => recompute_rates(args.from, args.to)?,
+    };
+
+    let report = accrue(
+        &rates,
+        &balances,
+        args.from,
+        args.to,
+        args.period,
+        args.decimals,
+    )?;
+
+    // Daily accruals
+    println!("date,rate,rate_date,balance,balance_date,amount");
+    for day in &report.days {
+        println!(
+            "{},{},{},{},{},{}",
+            day.date, day.rate, day.rate_date, day.balance, day.balance_date, day.amount
+        );
+    }
+
+    // Period accruals
+    println!();
+    println!("period_start,period_end,days,total,total_rounded,sum_of_rounded_days,residual");
+    for period in &report.periods {
+        println!(
+            "{},{},{},{},{},{},{}",
+            period.start,
+            period.end,
+            period.days,
+            period.total,
+            period.total_rounded,
+            period.sum_of_rounded_days,
+            period.rounding_residual()
+        );
+    }
+
+    // Reconciliation
+    let carried_rates = report.carried_rates().count();
+    let carried_balances = report.carried_balances().count();
+    info!(
+        "Accrued {} over {} day(s) in {} period(s)",
+        report.total,
+        report.days.len(),
+        report.periods.len()
+    );
+    if carried_rates > 0 {
+        warn!("{carried_rates} day(s) used a carried-forward AQA rate");
+    }
+    if carried_balances > 0 {
+        warn!("{carried_balances} day(s) used a carried-forward reserve balance");
+    }
+    report
+        .reconcile(&rates, &balances)
+        .context("Reconciliation failed")?;
+    info!("Period totals reconcile with daily accruals and input balances and rates");
+
+    Ok(())
+}
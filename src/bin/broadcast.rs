@@ -0,0 +1,39 @@
+use anyhow::{Context, Result, bail};
+use std::env;
+use std::path::PathBuf;
+
+use aqa_publisher::chain::Network;
+use aqa_publisher::utils::broadcast_signed;
+
+const USAGE: &str = "Usage: broadcast <signed.json>... [--network mainnet|testnet]";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Load environment variables
+    let _ = dotenvy::dotenv();
+    env_logger::init();
+
+    // Parse arguments; network defaults to `NETWORK`
+    let mut paths = Vec::new();
+    let mut network = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--network" => {
+                let name = args.next().context("--network requires a value")?;
+                network = Some(name.parse::<Network>()?);
+            }
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    if paths.is_empty() {
+        bail!("at least one signed request is required\n{USAGE}");
+    }
+    let network = match network {
+        Some(network) => network,
+        None => Network::from_env()?,
+    };
+
+    // Validate and post each signed request
+    broadcast_signed(&network, &paths).await
+}
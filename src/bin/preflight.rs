@@ -0,0 +1,35 @@
+use anyhow::{Result, bail};
+
+use aqa_publisher::chain::{HyperliquidClient, Network, preflight::check_signers};
+use aqa_publisher::utils::load_signers;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Load environment variables
+    let _ = dotenvy::dotenv();
+    env_logger::init();
+
+    // Validate network; unknown values are rejected
+    let network = Network::from_env()?;
+
+    // Check each signer enabled on the network and print its status
+    let signers = load_signers(&network)?;
+    let client = HyperliquidClient::new(network.clone());
+    let statuses = check_signers(&client, &signers).await?;
+    let mut failure_count = 0;
+    for (name, status) in &statuses {
+        println!("{name}: {status}");
+        if !status.is_authorized() {
+            failure_count += 1;
+        }
+    }
+
+    // Fail if any signer is not authorized
+    if failure_count > 0 {
+        bail!(
+            "{failure_count} of {} signer(s) not authorized on {network}",
+            statuses.len()
+        );
+    }
+    Ok(())
+}
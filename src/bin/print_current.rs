@@ -2,7 +2,7 @@ use anyhow::Result;
 use chrono::Local;
 use log::info;
 
-use aqa_publisher::{get_aqa_ref_rate, utils::fmt_scaled_rate};
+use aqa_publisher::{formula::FormulaSet, get_aqa_ref_rate, utils::fmt_scaled_rate};
 
 fn main() -> Result<()> {
     // Load environment variables
@@ -11,8 +11,9 @@ fn main() -> Result<()> {
 
     // Collect and log AQA rate
     let date = Local::now().date_naive();
-    let (median_date, _, aqa_ref_rate) = get_aqa_ref_rate(date)?;
-    info!("AQA rate on {}: {}", median_date, aqa_ref_rate);
+    let formulas = FormulaSet::from_env()?;
+    let (median_date, _, aqa_ref_rate) = get_aqa_ref_rate(date, &formulas)?;
+    info!("AQA rate on {median_date}: {aqa_ref_rate}");
     info!(
         "Submission-formatted rate: {}",
         fmt_scaled_rate(aqa_ref_rate)
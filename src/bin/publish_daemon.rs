@@ -1,11 +1,13 @@
 use anyhow::{Context, Result};
 use chrono::{Local, Utc};
 use log::{error, info};
-use std::env;
-use tokio::time::sleep;
+use tokio::time::{Instant, sleep, timeout_at};
 
+use aqa_publisher::chain::Network;
+use aqa_publisher::sources::health;
 use aqa_publisher::utils::{
-    duration_until_next_execution, fetch_and_publish_aqa, fetch_aqa, fmt_duration,
+    duration_until_next_execution, fetch_and_publish_aqa, fetch_aqa, fmt_duration, load_signers,
+    run_budget, run_preflight,
 };
 
 // Fixed execution time: 10 PM UTC (22:00)
@@ -17,19 +19,28 @@ async fn main() -> Result<()> {
     let _ = dotenvy::dotenv();
     env_logger::init();
 
-    // Check for publisher private key(s)
-    if env::var("PUBLISHER_PRIVATE_KEY").is_err() {
-        anyhow::bail!("PUBLISHER_PRIVATE_KEY environment variable must be set");
-    }
+    // Validate network before anything else; unknown values are rejected
+    let network = Network::from_env()?;
+    info!("Network: {network}");
+
+    // Check for publisher signer(s) enabled on the network
+    let signers = load_signers(&network)?;
+    info!("Loaded {} publishing signer(s)", signers.len());
+
+    // Refuse to start with signers not authorized for an active validator
+    run_preflight(&network, &signers).await?;
+
+    // Skip persistently failing sources across daily runs
+    health::enable_breaker();
+
+    // Every run must finish (or give up) within this budget
+    let budget = run_budget()?;
 
     // Execute startup check (no-op)
-    let (median_date, _, aqa_ref_rate) = fetch_aqa()
+    let (median_date, _, aqa_ref_rate) = fetch_aqa(Some((Instant::now() + budget).into_std()))
         .await
         .context("Failed to fetch data on startup")?;
-    info!(
-        "Executed startup data fetch (rate: {} on {})",
-        aqa_ref_rate, median_date
-    );
+    info!("Executed startup data fetch (rate: {aqa_ref_rate} on {median_date})");
 
     // Calculate next scheduled execution
     let duration_until_next = duration_until_next_execution(EXECUTION_HOUR_UTC);
@@ -48,9 +59,19 @@ async fn main() -> Result<()> {
         // Fetch and publish data
         info!("\n--- Scheduled run at {} ---", Utc::now());
         info!("Local time: {}", Local::now());
-        if let Err(e) = fetch_and_publish_aqa().await {
-            error!("Error during scheduled run: {}", e);
+        let deadline = Instant::now() + budget;
+        info!("Run deadline: {}", fmt_duration(budget));
+        match timeout_at(
+            deadline,
+            fetch_and_publish_aqa(&network, &signers, Some(deadline.into_std())),
+        )
+        .await
+        {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => error!("Error during scheduled run: {e}"),
+            Err(_) => error!("Scheduled run did not finish before its deadline"),
         }
+        health::registry().log_summary();
 
         // Setup next scheduled execution
         let next_duration = duration_until_next_execution(EXECUTION_HOUR_UTC);
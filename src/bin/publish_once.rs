@@ -1,7 +1,12 @@
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 use std::env;
+use std::path::PathBuf;
+use std::time::Instant;
 
-use aqa_publisher::utils::fetch_and_publish_aqa;
+use aqa_publisher::chain::Network;
+use aqa_publisher::utils::{dry_run_aqa, fetch_and_publish_aqa, load_signers, run_budget};
+
+const USAGE: &str = "Usage: publish_once [--dry-run [--out <dir>]]";
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -9,11 +14,40 @@ async fn main() -> Result<()> {
     let _ = dotenvy::dotenv();
     env_logger::init();
 
-    // Check for publisher private key(s)
-    if env::var("PUBLISHER_PRIVATE_KEY").is_err() {
-        anyhow::bail!("PUBLISHER_PRIVATE_KEY environment variable must be set");
+    // Parse arguments; a dry run prints requests unless `--out` is given
+    let mut dry_run = false;
+    let mut out: Option<PathBuf> = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--out" => out = Some(args.next().context("--out requires a value")?.into()),
+            _ => bail!("unexpected argument '{arg}'\n{USAGE}"),
+        }
+    }
+    if out.is_some() && !dry_run {
+        bail!("--out requires --dry-run\n{USAGE}");
+    }
+
+    // Validate network; unknown values are rejected
+    let network = Network::from_env()?;
+
+    // Check for publisher signer(s) enabled on the network
+    let signers = load_signers(&network)?;
+    let deadline = Some(Instant::now() + run_budget()?);
+
+    // Sign without posting, printing or writing each request
+    if dry_run {
+        for request in dry_run_aqa(&network, &signers, deadline).await? {
+            match &out {
+                Some(dir) => println!("{}", request.write_to(dir)?.display()),
+                None => println!("{}", serde_json::to_string_pretty(&request)?),
+            }
+        }
+        return Ok(());
     }
 
     // Fetch and publish data
-    Ok(fetch_and_publish_aqa().await?)
+    fetch_and_publish_aqa(&network, &signers, deadline).await?;
+    Ok(())
 }
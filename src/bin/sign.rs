@@ -0,0 +1,53 @@
+use anyhow::{Context, Result, bail};
+use std::env;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use aqa_publisher::chain::Network;
+use aqa_publisher::utils::{load_signers, run_budget, sign_votes_offline};
+
+const USAGE: &str = "Usage: sign [--rate <decimal>] [--out <dir>] [--network mainnet|testnet]
+
+Signed votes must be broadcast while their nonce (the signing time) is less than 2 days
+old and no more than 1 day ahead of the exchange's clock, and before VOTE_EXPIRES_AFTER_SECS
+elapses if set; `broadcast` rejects files outside that window.";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Load environment variables
+    let _ = dotenvy::dotenv();
+    env_logger::init();
+
+    // Parse arguments; network defaults to `NETWORK`, output to the working directory
+    let mut rate = None;
+    let mut out = PathBuf::from(".");
+    let mut network = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rate" => rate = Some(args.next().context("--rate requires a value")?),
+            "--out" => out = args.next().context("--out requires a value")?.into(),
+            "--network" => {
+                let name = args.next().context("--network requires a value")?;
+                network = Some(name.parse::<Network>()?);
+            }
+            "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ => bail!("unexpected argument '{arg}'\n{USAGE}"),
+        }
+    }
+    let network = match network {
+        Some(network) => network,
+        None => Network::from_env()?,
+    };
+
+    // Sign votes and print written files
+    let signers = load_signers(&network)?;
+    let deadline = Some(Instant::now() + run_budget()?);
+    for path in sign_votes_offline(&network, &signers, rate.as_deref(), deadline, &out).await? {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
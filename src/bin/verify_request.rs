@@ -0,0 +1,48 @@
+use anyhow::{Context, Result, bail};
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+
+use aqa_publisher::chain::{Network, signing::verify_vote_request};
+
+const USAGE: &str = "Usage: verify_request <request.json | -> [--network mainnet|testnet]";
+
+fn main() -> Result<()> {
+    // Load environment variables
+    let _ = dotenvy::dotenv();
+    env_logger::init();
+
+    // Parse arguments; network defaults to `NETWORK`
+    let mut path = None;
+    let mut network = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--network" => {
+                let name = args.next().context("--network requires a value")?;
+                network = Some(name.parse::<Network>()?);
+            }
+            _ if path.is_none() => path = Some(arg),
+            _ => bail!("unexpected argument '{arg}'\n{USAGE}"),
+        }
+    }
+    let path = path.with_context(|| format!("request path is required\n{USAGE}"))?;
+    let network = match network {
+        Some(network) => network,
+        None => Network::from_env()?,
+    };
+
+    // Read captured request (`-` for stdin)
+    let request = if path == "-" {
+        let mut request = String::new();
+        io::stdin().read_to_string(&mut request)?;
+        request
+    } else {
+        fs::read_to_string(&path).with_context(|| format!("reading {path}"))?
+    };
+
+    // Print recovered signer
+    let signer = verify_vote_request(&request, &network)?;
+    println!("{signer}");
+    Ok(())
+}
@@ -1,87 +1,264 @@
+use std::sync::Arc;
 use std::time::Duration;
 
-use alloy::signers::local::PrivateKeySigner;
-use anyhow::{Result, bail};
+use alloy::primitives::Address;
+use anyhow::{Context, Result, bail};
 use chrono::prelude::Utc;
+use log::{debug, warn};
 use reqwest::Client;
-use serde_json::Value;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use tokio::sync::OnceCell;
 
-use super::signing::get_signed_vote;
-use super::types::{ExchangeRequest, ExchangeResponse, SignatureReq};
+use super::confirmation::L1VoteEntry;
+use super::error::ExchangeError;
+use super::network::Network;
+use super::nonce::{NonceManager, check_clock_skew};
+use super::preflight::{UserRole, ValidatorSummary};
+use super::signer::VoteSigner;
+use super::signing::{decode_signature, recover_l1_action_signer, sign_l1_action};
+use super::types::{
+    ActionOptions, ActionResponse, ExchangeRequest, ExchangeResponse, L1Action, SignatureReq,
+    ValidatorL1StreamAction,
+};
 
-/// Hyperliquid Mainnet `/exchange` URL
-const MAINNET_API_EXCHANGE_URL: &str = "https://api.hyperliquid.xyz/exchange";
-
-/// Hyperliquid Testnet `/exchange` URL
-const TESTNET_API_EXCHANGE_URL: &str = "https://api.hyperliquid-testnet.xyz/exchange";
-
-/// Minimal Hyperliquid request client
+/// Minimal Hyperliquid request client, shared by all signers
 pub struct HyperliquidClient {
     http: Client,
-    is_mainnet: bool,
-    exchange_url: &'static str,
-    wallet: PrivateKeySigner,
+    network: Network,
+    exchange_url: String,
+    info_url: String,
+    /// Vault address signed into every action, if any
+    vault_address: Option<Address>,
+    /// Validity window of a signed action, measured from its nonce
+    expires_in: Option<Duration>,
+    /// Source of strictly increasing nonces, shared across clients
+    nonces: Arc<NonceManager>,
+    /// Largest tolerated difference from the server clock; `None` skips the check
+    max_clock_skew: Option<Duration>,
+    /// Set once the clock skew check has passed, so it runs once per client
+    skew_checked: OnceCell<()>,
 }
 
+/// Default largest tolerated difference from the server clock
+pub const DEFAULT_MAX_CLOCK_SKEW: Duration = Duration::from_secs(30);
+
 impl HyperliquidClient {
-    pub fn new(wallet: PrivateKeySigner, is_mainnet: bool) -> Self {
+    pub fn new(network: Network) -> Self {
         // Setup shared request client
         let http = Client::builder()
             .timeout(Duration::from_secs(10))
             .build()
             .expect("Failed to build HTTP client");
 
-        // Initialize exchange URL based on selected network
-        let exchange_url = if is_mainnet {
-            MAINNET_API_EXCHANGE_URL
-        } else {
-            TESTNET_API_EXCHANGE_URL
-        };
+        // Initialize exchange and info URLs based on selected network
+        let exchange_url = network.exchange_url();
+        let info_url = network.info_url();
 
         Self {
             http,
-            is_mainnet,
+            network,
             exchange_url,
-            wallet,
+            info_url,
+            vault_address: None,
+            expires_in: None,
+            nonces: Arc::new(NonceManager::in_memory()),
+            max_clock_skew: Some(DEFAULT_MAX_CLOCK_SKEW),
+            skew_checked: OnceCell::new(),
         }
     }
 
-    /// Submit vote for a `rate` via `validatorL1Stream` HyperCore action
-    pub async fn submit_vote(&self, rate: &str) -> Result<Value> {
-        // Generate nonce, build action, sign action
-        let nonce = Utc::now().timestamp_millis() as u64;
-        let (action, signature) = get_signed_vote(&self.wallet, self.is_mainnet, nonce, rate)?;
+    /// Draw nonces from `nonces` (e.g., a persisted manager shared by all signers)
+    pub fn with_nonce_manager(mut self, nonces: Arc<NonceManager>) -> Self {
+        self.nonces = nonces;
+        self
+    }
+
+    /// Largest tolerated clock skew against the server before signing; `None` disables
+    pub fn with_max_clock_skew(mut self, max_clock_skew: Option<Duration>) -> Self {
+        self.max_clock_skew = max_clock_skew;
+        self
+    }
+
+    /// Sign actions with an `expiresAfter` of nonce + `expires_in`, bounding the window in
+    /// which a leaked or delayed signed vote can be replayed
+    pub fn with_expires_in(mut self, expires_in: Duration) -> Self {
+        self.expires_in = Some(expires_in);
+        self
+    }
+
+    /// Sign actions on behalf of `vault_address`
+    pub fn with_vault_address(mut self, vault_address: Address) -> Self {
+        self.vault_address = Some(vault_address);
+        self
+    }
+
+    /// Hashing options for an action signed at `nonce`
+    fn options(&self, nonce: u64) -> ActionOptions {
+        ActionOptions {
+            vault_address: self.vault_address,
+            expires_after: self
+                .expires_in
+                .map(|window| nonce + window.as_millis() as u64),
+        }
+    }
+
+    /// Compare local time against the server's `Date` header, failing on excessive skew
+    /// A missing `Date` header is logged and tolerated
+    pub async fn check_clock_skew(&self, max_skew: Duration) -> Result<()> {
+        let resp = self
+            .http
+            .head(&self.info_url)
+            .send()
+            .await
+            .context("Failed to query server time")?;
+        let Some(date) = resp.headers().get(reqwest::header::DATE) else {
+            warn!("Server response has no Date header; skipping clock skew check");
+            return Ok(());
+        };
+        let skew = check_clock_skew(date.to_str()?, Utc::now(), max_skew)?;
+        debug!("Clock skew against server: {}s", skew.as_secs());
+        Ok(())
+    }
+
+    /// Submit vote for a `rate` signed by `signer` via `validatorL1Stream` HyperCore action
+    /// Exchange and HTTP failures are returned as an `ExchangeError` (see `downcast_ref`)
+    pub async fn submit_vote<S: VoteSigner + ?Sized>(
+        &self,
+        signer: &S,
+        rate: &str,
+    ) -> Result<ActionResponse> {
+        self.submit_action(signer, ValidatorL1StreamAction::new(rate))
+            .await
+    }
+
+    /// Sign `action` with `signer` and submit it to the exchange
+    /// Exchange and HTTP failures are returned as an `ExchangeError` (see `downcast_ref`)
+    pub async fn submit_action<A: L1Action, S: VoteSigner + ?Sized>(
+        &self,
+        signer: &S,
+        action: A,
+    ) -> Result<ActionResponse> {
+        let request = self.sign_action(signer, action).await?;
+        self.broadcast(&request).await
+    }
+
+    /// Sign `action` with `signer` into a self-verified request, without sending it
+    pub async fn sign_action<A: L1Action, S: VoteSigner + ?Sized>(
+        &self,
+        signer: &S,
+        action: A,
+    ) -> Result<ExchangeRequest<'static, A>> {
+        // Refuse to sign with a clock far from the server's (checked once per client)
+        if let Some(max_skew) = self.max_clock_skew {
+            self.skew_checked
+                .get_or_try_init(|| self.check_clock_skew(max_skew))
+                .await?;
+        }
+
+        // Generate nonce, sign action
+        let nonce = self
+            .nonces
+            .next(signer.address(), Utc::now().timestamp_millis() as u64)?;
+        let options = self.options(nonce);
+        let signature = sign_l1_action(signer, &self.network, &action, nonce, &options).await?;
 
         // Construct payload
         let request = ExchangeRequest {
             action,
             nonce,
             // Encode signature in expected format
-            signature: SignatureReq {
-                r: &format!("0x{:x}", signature.r()),
-                s: &format!("0x{:x}", signature.s()),
-                v: 27 + signature.v() as u64,
-            },
+            signature: SignatureReq::from_signature(&signature),
+            vault_address: options.vault_address,
+            expires_after: options.expires_after,
         };
 
+        // Self-verify: the encoded signature must recover to our signer before broadcasting
+        let recovered = recover_l1_action_signer(
+            &request.action,
+            nonce,
+            &options,
+            &self.network,
+            &decode_signature(&request.signature)?,
+        )?;
+        if recovered != signer.address() {
+            bail!(
+                "Signature self-verification failed: recovered {recovered}, expected {}",
+                signer.address()
+            );
+        }
+        Ok(request)
+    }
+
+    /// Post an already signed `request` to the exchange
+    /// Exchange and HTTP failures are returned as an `ExchangeError` (see `downcast_ref`)
+    pub async fn broadcast<A: L1Action>(
+        &self,
+        request: &ExchangeRequest<'_, A>,
+    ) -> Result<ActionResponse> {
         // Send request
         let resp = self
             .http
-            .post(self.exchange_url)
-            .json(&request)
+            .post(&self.exchange_url)
+            .json(request)
             .send()
-            .await?;
+            .await
+            .map_err(|e| ExchangeError::from_reqwest(&e))?;
 
         // Assert response success, 200-299
-        if !resp.status().is_success() {
-            bail!("HTTP error: {}", resp.status());
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(ExchangeError::from_status(status, &body).into());
         }
 
         // Parse response
         // Standard action response deserializes based on `status` key in response payload
-        match resp.json::<ExchangeResponse>().await? {
+        let body = resp
+            .bytes()
+            .await
+            .map_err(|e| ExchangeError::from_reqwest(&e))?;
+        match serde_json::from_slice::<ExchangeResponse>(&body)
+            .map_err(|e| ExchangeError::Decode(e.to_string()))?
+        {
             ExchangeResponse::Ok { response } => Ok(response),
-            ExchangeResponse::Err { response } => bail!("API Error: {}", response),
+            ExchangeResponse::Err { response } => {
+                Err(ExchangeError::from_message(&response).into())
+            }
+        }
+    }
+
+    /// Query the info API for every validator's current L1 stream votes, to confirm votes
+    /// the exchange accepted (see `confirmation::confirm_vote`)
+    pub async fn validator_l1_votes(&self) -> Result<Vec<L1VoteEntry>> {
+        self.info(json!({ "type": "validatorL1Votes" })).await
+    }
+
+    /// Query the info API for the current validator set
+    pub async fn validator_summaries(&self) -> Result<Vec<ValidatorSummary>> {
+        self.info(json!({ "type": "validatorSummaries" })).await
+    }
+
+    /// Query the info API for the user that approved `address` as an agent, if it is one
+    pub async fn agent_of(&self, address: Address) -> Result<Option<Address>> {
+        let role: UserRole = self
+            .info(json!({ "type": "userRole", "user": address }))
+            .await?;
+        Ok(match role {
+            UserRole::Agent { data } => Some(data.user),
+            UserRole::Other => None,
+        })
+    }
+
+    /// Post an info request and parse its response
+    async fn info<T: DeserializeOwned>(&self, request: Value) -> Result<T> {
+        let resp = self.http.post(&self.info_url).json(&request).send().await?;
+
+        // Assert response success, 200-299
+        if !resp.status().is_success() {
+            bail!("HTTP error: {}", resp.status());
         }
+
+        Ok(resp.json().await?)
     }
 }
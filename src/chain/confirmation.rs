@@ -0,0 +1,130 @@
+use std::fmt;
+use std::str::FromStr;
+
+use alloy::primitives::Address;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+/// Outcome of checking the info API for a validator's current risk-free-rate vote
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VoteConfirmation {
+    /// Current vote matches the submitted rate
+    Confirmed,
+    /// Current vote is for a different rate
+    Mismatch { expected: String, found: String },
+    /// No current vote from the validator
+    NotFound,
+}
+
+impl VoteConfirmation {
+    pub fn is_confirmed(&self) -> bool {
+        matches!(self, Self::Confirmed)
+    }
+}
+
+impl fmt::Display for VoteConfirmation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Confirmed => write!(f, "confirmed"),
+            Self::Mismatch { expected, found } => {
+                write!(f, "mismatch (expected {expected}, found {found})")
+            }
+            Self::NotFound => write!(f, "not found"),
+        }
+    }
+}
+
+/// Single entry of the `validatorL1Votes` info response
+/// Dev: unknown fields are ignored; entries without a `riskFreeRate` are other stream votes
+#[derive(Debug, Deserialize)]
+pub struct L1VoteEntry {
+    pub action: L1VoteAction,
+    /// Validators currently voting for `action`
+    #[serde(default)]
+    pub votes: Vec<Address>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1VoteAction {
+    pub risk_free_rate: Option<String>,
+}
+
+/// Compare the current vote of `validator` against the submitted `rate`
+///
+/// Votes are recorded against the validator, not the signer or agent key that signed them.
+/// Rates are compared numerically, so `0.0350` confirms a submitted `0.03500000`.
+pub fn confirm_vote(entries: &[L1VoteEntry], validator: Address, rate: &str) -> VoteConfirmation {
+    let Some(found) = entries
+        .iter()
+        .filter(|e| e.votes.contains(&validator))
+        .find_map(|e| e.action.risk_free_rate.as_deref())
+    else {
+        return VoteConfirmation::NotFound;
+    };
+
+    let same = match (Decimal::from_str(found), Decimal::from_str(rate)) {
+        (Ok(found), Ok(rate)) => found == rate,
+        _ => found == rate,
+    };
+    if same {
+        VoteConfirmation::Confirmed
+    } else {
+        VoteConfirmation::Mismatch {
+            expected: rate.to_string(),
+            found: found.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+
+    const VOTER: Address = address!("0x1111111111111111111111111111111111111111");
+    const OTHER: Address = address!("0x2222222222222222222222222222222222222222");
+
+    fn entries(json: &str) -> Vec<L1VoteEntry> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn confirms_matching_vote() {
+        let votes = entries(
+            r#"[
+                {"action": {"riskFreeRate": "0.0350"}, "votes": ["0x1111111111111111111111111111111111111111"]},
+                {"action": {"riskFreeRate": "0.0400"}, "votes": ["0x2222222222222222222222222222222222222222"]}
+            ]"#,
+        );
+        assert_eq!(
+            confirm_vote(&votes, VOTER, "0.03500000"),
+            VoteConfirmation::Confirmed
+        );
+    }
+
+    #[test]
+    fn reports_mismatch_and_not_found() {
+        let votes = entries(
+            r#"[
+                {"action": {"someOtherStream": "1"}, "votes": ["0x1111111111111111111111111111111111111111"]},
+                {"action": {"riskFreeRate": "0.0400"}, "votes": ["0x1111111111111111111111111111111111111111"]}
+            ]"#,
+        );
+        assert_eq!(
+            confirm_vote(&votes, VOTER, "0.03500000"),
+            VoteConfirmation::Mismatch {
+                expected: "0.03500000".to_string(),
+                found: "0.0400".to_string()
+            }
+        );
+        assert_eq!(
+            confirm_vote(&votes, OTHER, "0.03500000"),
+            VoteConfirmation::NotFound
+        );
+        assert_eq!(
+            confirm_vote(&[], VOTER, "0.03500000"),
+            VoteConfirmation::NotFound
+        );
+    }
+}
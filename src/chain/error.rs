@@ -0,0 +1,214 @@
+use std::fmt;
+
+use reqwest::StatusCode;
+
+/// How the caller should react to a failed exchange request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Disposition {
+    /// Transient; the same vote may succeed if re-signed and resent
+    Retry,
+    /// Signer or validator configuration problem that needs an operator
+    Alert,
+    /// Permanent for this request; resending will not help
+    GiveUp,
+}
+
+impl fmt::Display for Disposition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Retry => write!(f, "retry"),
+            Self::Alert => write!(f, "alert"),
+            Self::GiveUp => write!(f, "give up"),
+        }
+    }
+}
+
+/// Failure of an `/exchange` request, classified from the transport, HTTP status or the
+/// exchange's error message
+/// Dev: the exchange reports failures as free-form strings, so classification is by
+/// substring; unrecognised messages are kept as `Rejected`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExchangeError {
+    /// Signer is not a registered signer or agent for any validator
+    UnauthorizedSigner(String),
+    /// Signer's validator is not an active validator
+    NotValidator(String),
+    /// Nonce was reused, too old or too far in the future
+    InvalidNonce(String),
+    /// Exchange is rate limiting this signer or address
+    RateLimited(String),
+    /// Action or request body could not be parsed by the exchange
+    MalformedAction(String),
+    /// Any other exchange rejection
+    Rejected(String),
+    /// Server answered with a non-success HTTP status
+    Http { status: StatusCode, body: String },
+    /// Sending the request or reading the response timed out
+    Timeout(String),
+    /// Connection could not be established
+    Connect(String),
+    /// Response was not a valid exchange response
+    Decode(String),
+}
+
+impl ExchangeError {
+    /// Classify an exchange `err` response message
+    pub fn from_message(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        let message = message.to_string();
+        if lower.contains("nonce") {
+            Self::InvalidNonce(message)
+        } else if lower.contains("rate limit") || lower.contains("too many") {
+            Self::RateLimited(message)
+        } else if lower.contains("not a validator")
+            || lower.contains("not an active validator")
+            || lower.contains("validator not")
+            || lower.contains("jailed")
+        {
+            Self::NotValidator(message)
+        } else if lower.contains("does not exist")
+            || lower.contains("unauthorized")
+            || lower.contains("not authorized")
+            || lower.contains("invalid signer")
+        {
+            Self::UnauthorizedSigner(message)
+        } else if lower.contains("deserialize")
+            || lower.contains("malformed")
+            || lower.contains("invalid action")
+        {
+            Self::MalformedAction(message)
+        } else {
+            Self::Rejected(message)
+        }
+    }
+
+    /// Classify a non-success HTTP response; `422` is how the exchange rejects a body it
+    /// cannot parse
+    pub fn from_status(status: StatusCode, body: &str) -> Self {
+        let body = body.trim().to_string();
+        if status == StatusCode::UNPROCESSABLE_ENTITY {
+            Self::MalformedAction(body)
+        } else {
+            Self::Http { status, body }
+        }
+    }
+
+    /// Classify a transport-level failure
+    pub fn from_reqwest(e: &reqwest::Error) -> Self {
+        if e.is_timeout() {
+            Self::Timeout(e.to_string())
+        } else if e.is_connect() {
+            Self::Connect(e.to_string())
+        } else if let Some(status) = e.status() {
+            Self::from_status(status, "")
+        } else if e.is_decode() {
+            Self::Decode(e.to_string())
+        } else {
+            Self::Connect(e.to_string())
+        }
+    }
+
+    /// Whether resending (re-signed with a fresh nonce) could succeed: timeouts,
+    /// connection errors, 5xx, 408 and 429 responses, rate limiting and rejected nonces
+    pub fn is_transient(&self) -> bool {
+        self.disposition() == Disposition::Retry
+    }
+
+    pub fn disposition(&self) -> Disposition {
+        match self {
+            Self::Timeout(_) | Self::Connect(_) | Self::RateLimited(_) | Self::InvalidNonce(_) => {
+                Disposition::Retry
+            }
+            Self::Http { status, .. } => {
+                if status.is_server_error()
+                    || *status == StatusCode::TOO_MANY_REQUESTS
+                    || *status == StatusCode::REQUEST_TIMEOUT
+                {
+                    Disposition::Retry
+                } else {
+                    Disposition::GiveUp
+                }
+            }
+            Self::UnauthorizedSigner(_) | Self::NotValidator(_) => Disposition::Alert,
+            Self::MalformedAction(_) | Self::Rejected(_) | Self::Decode(_) => Disposition::GiveUp,
+        }
+    }
+}
+
+impl fmt::Display for ExchangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnauthorizedSigner(message) => write!(f, "unauthorized signer: {message}"),
+            Self::NotValidator(message) => write!(f, "not a validator: {message}"),
+            Self::InvalidNonce(message) => write!(f, "invalid nonce: {message}"),
+            Self::RateLimited(message) => write!(f, "rate limited: {message}"),
+            Self::MalformedAction(message) => write!(f, "malformed action: {message}"),
+            Self::Rejected(message) => write!(f, "API Error: {message}"),
+            Self::Http { status, body } if body.is_empty() => write!(f, "HTTP error: {status}"),
+            Self::Http { status, body } => write!(f, "HTTP error: {status}: {body}"),
+            Self::Timeout(message) => write!(f, "timed out: {message}"),
+            Self::Connect(message) => write!(f, "connection failed: {message}"),
+            Self::Decode(message) => write!(f, "invalid exchange response: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ExchangeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_exchange_messages() {
+        let cases = [
+            (
+                "User or API Wallet 0x1111111111111111111111111111111111111111 does not exist.",
+                Disposition::Alert,
+            ),
+            ("Signer is not an active validator", Disposition::Alert),
+            ("Invalid nonce: duplicate nonce", Disposition::Retry),
+            (
+                "Too many cumulative requests sent for this address",
+                Disposition::Retry,
+            ),
+            ("Failed to deserialize the JSON body", Disposition::GiveUp),
+            ("Something unexpected", Disposition::GiveUp),
+        ];
+        for (message, disposition) in cases {
+            assert_eq!(
+                ExchangeError::from_message(message).disposition(),
+                disposition,
+                "{message}"
+            );
+        }
+        assert!(matches!(
+            ExchangeError::from_message("User or API Wallet 0x11 does not exist."),
+            ExchangeError::UnauthorizedSigner(_)
+        ));
+        assert!(matches!(
+            ExchangeError::from_message("Signer is not an active validator"),
+            ExchangeError::NotValidator(_)
+        ));
+    }
+
+    #[test]
+    fn classifies_http_statuses() {
+        for status in [
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::BAD_GATEWAY,
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::REQUEST_TIMEOUT,
+        ] {
+            assert!(ExchangeError::from_status(status, "").is_transient());
+        }
+        assert_eq!(
+            ExchangeError::from_status(StatusCode::BAD_REQUEST, "").disposition(),
+            Disposition::GiveUp
+        );
+        assert!(matches!(
+            ExchangeError::from_status(StatusCode::UNPROCESSABLE_ENTITY, "bad body\n"),
+            ExchangeError::MalformedAction(body) if body == "bad body"
+        ));
+    }
+}
@@ -0,0 +1,187 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{Context, Result, bail};
+
+/// Where keystore passwords are read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PasswordSource {
+    /// File containing the password(s)
+    File(PathBuf),
+    /// Inherited file descriptor (e.g., a pipe from a secrets manager), read once
+    Fd(u32),
+}
+
+impl PasswordSource {
+    /// Source from `PUBLISHER_KEYSTORE_PASSWORD_FILE` or `PUBLISHER_KEYSTORE_PASSWORD_FD`
+    /// (exactly one must be set)
+    pub fn from_env() -> Result<Self> {
+        match (
+            env::var("PUBLISHER_KEYSTORE_PASSWORD_FILE"),
+            env::var("PUBLISHER_KEYSTORE_PASSWORD_FD"),
+        ) {
+            (Ok(path), Err(_)) => Ok(Self::File(path.trim().into())),
+            (Err(_), Ok(fd)) => Ok(Self::Fd(
+                fd.trim()
+                    .parse()
+                    .context("Invalid PUBLISHER_KEYSTORE_PASSWORD_FD")?,
+            )),
+            (Ok(_), Ok(_)) => bail!(
+                "Set only one of PUBLISHER_KEYSTORE_PASSWORD_FILE and PUBLISHER_KEYSTORE_PASSWORD_FD"
+            ),
+            (Err(_), Err(_)) => bail!(
+                "PUBLISHER_KEYSTORE requires PUBLISHER_KEYSTORE_PASSWORD_FILE or PUBLISHER_KEYSTORE_PASSWORD_FD"
+            ),
+        }
+    }
+
+    /// Read the full contents of the source
+    /// Dev: descriptors are read through `/dev/fd`, avoiding unsafe `from_raw_fd`
+    pub fn read(&self) -> Result<String> {
+        match self {
+            Self::File(path) => fs::read_to_string(path)
+                .with_context(|| format!("Failed to read password file {}", path.display())),
+            Self::Fd(fd) => fs::read_to_string(format!("/dev/fd/{fd}"))
+                .with_context(|| format!("Failed to read password from file descriptor {fd}")),
+        }
+    }
+}
+
+/// Split password source contents into one password per keystore: a single line is
+/// shared by all keystores, otherwise there must be exactly one line per keystore
+/// Dev: only line endings are stripped; other whitespace is part of the password
+pub fn passwords_for(contents: &str, keystores: usize) -> Result<Vec<String>> {
+    let lines: Vec<&str> = contents
+        .strip_suffix('\n')
+        .unwrap_or(contents)
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+    match lines.len() {
+        1 => Ok(vec![lines[0].to_string(); keystores]),
+        n if n == keystores => Ok(lines.into_iter().map(str::to_string).collect()),
+        n => bail!(
+            "Password source has {n} lines; expected 1 (shared) or {keystores} (one per keystore)"
+        ),
+    }
+}
+
+/// Decrypt an Ethereum JSON keystore (v3, scrypt or pbkdf2)
+pub fn decrypt_keystore(path: &Path, password: &str) -> Result<PrivateKeySigner> {
+    PrivateKeySigner::decrypt_keystore(path, password)
+        .with_context(|| format!("Failed to decrypt keystore {}", path.display()))
+}
+
+/// Decrypt the keystores in `PUBLISHER_KEYSTORE` (comma-separated paths) with passwords
+/// from `PasswordSource::from_env`; empty if `PUBLISHER_KEYSTORE` is not set
+pub fn load_keystores_from_env() -> Result<Vec<PrivateKeySigner>> {
+    let Ok(list) = env::var("PUBLISHER_KEYSTORE") else {
+        return Ok(Vec::new());
+    };
+    let paths: Vec<PathBuf> = list
+        .split(',')
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .collect();
+    if paths.is_empty() {
+        bail!("PUBLISHER_KEYSTORE is set but lists no keystore files");
+    }
+
+    let passwords = passwords_for(&PasswordSource::from_env()?.read()?, paths.len())?;
+    paths
+        .iter()
+        .zip(&passwords)
+        .map(|(path, password)| decrypt_keystore(path, password))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+
+    mod passwords_for_tests {
+        use super::*;
+
+        #[test]
+        fn shared_and_per_keystore() {
+            assert_eq!(passwords_for("secret\n", 3).unwrap(), ["secret"; 3]);
+            assert_eq!(
+                passwords_for("one\r\n two \nthree", 3).unwrap(),
+                ["one", " two ", "three"]
+            );
+            assert_eq!(passwords_for("", 1).unwrap(), [""]);
+            assert!(passwords_for("one\ntwo\n", 3).is_err());
+        }
+    }
+
+    mod decrypt_keystore_tests {
+        use super::*;
+
+        /// PBKDF2 test vector from the Web3 Secret Storage Definition
+        const PBKDF2_KEYSTORE: &str = r#"{
+            "crypto": {
+                "cipher": "aes-128-ctr",
+                "cipherparams": {"iv": "6087dab2f9fdbbfaddc31a909735c1e6"},
+                "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+                "kdf": "pbkdf2",
+                "kdfparams": {
+                    "c": 262144,
+                    "dklen": 32,
+                    "prf": "hmac-sha256",
+                    "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+                },
+                "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+            },
+            "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+            "version": 3
+        }"#;
+
+        fn temp_dir(name: &str) -> PathBuf {
+            let dir = env::temp_dir().join(format!("aqa-keystore-{}-{name}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            dir
+        }
+
+        #[test]
+        fn decrypts_pbkdf2() {
+            let dir = temp_dir("pbkdf2");
+            let path = dir.join("keystore.json");
+            fs::write(&path, PBKDF2_KEYSTORE).unwrap();
+
+            let signer = decrypt_keystore(&path, "testpassword").unwrap();
+            assert_eq!(
+                signer.to_bytes().to_string(),
+                "0x7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d"
+            );
+            assert!(decrypt_keystore(&path, "wrong").is_err());
+            fs::remove_dir_all(dir).unwrap();
+        }
+
+        #[test]
+        fn decrypts_scrypt() {
+            let dir = temp_dir("scrypt");
+            let key = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+            let key: PrivateKeySigner = key.parse().unwrap();
+            PrivateKeySigner::encrypt_keystore(
+                &dir,
+                &mut rand::thread_rng(),
+                key.to_bytes(),
+                "secret",
+                Some("keystore.json"),
+            )
+            .unwrap();
+
+            let signer = decrypt_keystore(&dir.join("keystore.json"), "secret").unwrap();
+            assert_eq!(
+                signer.address(),
+                address!("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266")
+            );
+            fs::remove_dir_all(dir).unwrap();
+        }
+    }
+}
@@ -1,6 +1,22 @@
 pub mod api;
+pub mod confirmation;
+pub mod error;
+pub mod keystore;
+pub mod network;
+pub mod nonce;
+pub mod offline;
+pub mod pkcs11;
+pub mod preflight;
+pub mod signer;
+pub mod signer_config;
 pub mod signing;
+pub mod submit;
 pub mod types;
 
 // Re-export for ease of use
 pub use api::HyperliquidClient;
+pub use confirmation::VoteConfirmation;
+pub use error::ExchangeError;
+pub use network::Network;
+pub use nonce::NonceManager;
+pub use signer::{RemoteSigner, VoteSigner};
@@ -0,0 +1,179 @@
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Context, Result, anyhow, bail};
+use reqwest::Url;
+
+/// Hyperliquid Mainnet API URL
+const MAINNET_API_URL: &str = "https://api.hyperliquid.xyz";
+
+/// Hyperliquid Testnet API URL
+const TESTNET_API_URL: &str = "https://api.hyperliquid-testnet.xyz";
+
+/// EIP-712 chain id of the HyperCore L1 action domain (same on mainnet and testnet)
+pub const CORE_CHAIN_ID: u64 = 1337;
+
+/// Hyperliquid network votes are signed for and submitted to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    /// Any other API (e.g., a local node or mock exchange)
+    Custom {
+        /// Base API URL, without the `/exchange` or `/info` path
+        api_url: String,
+        /// Phantom agent source: `a` signs mainnet-style, `b` testnet-style
+        phantom_source: String,
+        /// EIP-712 domain chain id
+        chain_id: u64,
+    },
+}
+
+impl Network {
+    /// Load network from `NETWORK` (`mainnet`, `testnet` or `custom`), which must be set
+    ///
+    /// `custom` requires `HYPERLIQUID_API_URL` and accepts `HYPERLIQUID_PHANTOM_SOURCE`
+    /// (default `b`) and `HYPERLIQUID_CHAIN_ID` (default 1337).
+    /// Dev: no default, so a missing or misspelled variable can never fall back to mainnet
+    pub fn from_env() -> Result<Self> {
+        let name = env::var("NETWORK")
+            .map_err(|_| anyhow!("NETWORK must be set to mainnet, testnet or custom"))?;
+        if name.trim() != "custom" {
+            return name.parse();
+        }
+
+        let api_url = env::var("HYPERLIQUID_API_URL")
+            .map_err(|_| anyhow!("NETWORK=custom requires HYPERLIQUID_API_URL"))?;
+        let phantom_source = env::var("HYPERLIQUID_PHANTOM_SOURCE").unwrap_or_else(|_| "b".into());
+        let chain_id = match env::var("HYPERLIQUID_CHAIN_ID") {
+            Ok(id) => id.trim().parse().context("Invalid HYPERLIQUID_CHAIN_ID")?,
+            Err(_) => CORE_CHAIN_ID,
+        };
+        Self::custom(&api_url, &phantom_source, chain_id)
+    }
+
+    /// Validated custom network
+    pub fn custom(api_url: &str, phantom_source: &str, chain_id: u64) -> Result<Self> {
+        let api_url = api_url.trim().trim_end_matches('/');
+        let url = Url::parse(api_url)
+            .with_context(|| format!("Invalid Hyperliquid API URL '{api_url}'"))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            bail!("Hyperliquid API URL must be http(s), got '{api_url}'");
+        }
+        let phantom_source = phantom_source.trim();
+        if !matches!(phantom_source, "a" | "b") {
+            bail!(
+                "phantom agent source must be 'a' (mainnet) or 'b' (testnet), got '{phantom_source}'"
+            );
+        }
+        Ok(Self::Custom {
+            api_url: api_url.to_string(),
+            phantom_source: phantom_source.to_string(),
+            chain_id,
+        })
+    }
+
+    /// Base API URL
+    pub fn api_url(&self) -> &str {
+        match self {
+            Self::Mainnet => MAINNET_API_URL,
+            Self::Testnet => TESTNET_API_URL,
+            Self::Custom { api_url, .. } => api_url,
+        }
+    }
+
+    /// `/exchange` URL
+    pub fn exchange_url(&self) -> String {
+        format!("{}/exchange", self.api_url())
+    }
+
+    /// `/info` URL
+    pub fn info_url(&self) -> String {
+        format!("{}/info", self.api_url())
+    }
+
+    /// Phantom agent source signed over in L1 actions
+    /// Ref(Python): https://github.com/hyperliquid-dex/hyperliquid-python-sdk/blob/8baad667965968a020a2bb90d0287df0922ca941/hyperliquid/utils/signing.py#L178
+    pub fn phantom_source(&self) -> &str {
+        match self {
+            Self::Mainnet => "a",
+            Self::Testnet => "b",
+            Self::Custom { phantom_source, .. } => phantom_source,
+        }
+    }
+
+    /// EIP-712 domain chain id for L1 actions
+    pub fn chain_id(&self) -> u64 {
+        match self {
+            Self::Mainnet | Self::Testnet => CORE_CHAIN_ID,
+            Self::Custom { chain_id, .. } => *chain_id,
+        }
+    }
+}
+
+impl FromStr for Network {
+    type Err = anyhow::Error;
+
+    /// Parse `mainnet` or `testnet`; custom networks need `Network::custom`
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "mainnet" => Ok(Self::Mainnet),
+            "testnet" => Ok(Self::Testnet),
+            "custom" => bail!("custom network requires an API URL; use Network::custom"),
+            other => bail!("unknown network '{other}' (expected mainnet, testnet or custom)"),
+        }
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mainnet => write!(f, "mainnet"),
+            Self::Testnet => write!(f, "testnet"),
+            Self::Custom {
+                api_url,
+                phantom_source,
+                chain_id,
+            } => write!(
+                f,
+                "custom ({api_url}, phantom source {phantom_source}, chain id {chain_id})"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_known_networks() {
+        assert_eq!("mainnet".parse::<Network>().unwrap(), Network::Mainnet);
+        assert_eq!(" testnet ".parse::<Network>().unwrap(), Network::Testnet);
+        assert_eq!(
+            Network::Mainnet.exchange_url(),
+            "https://api.hyperliquid.xyz/exchange"
+        );
+        assert_eq!(Network::Testnet.phantom_source(), "b");
+        assert_eq!(Network::Mainnet.chain_id(), 1337);
+    }
+
+    #[test]
+    fn rejects_unknown_networks() {
+        for name in ["Mainnet", "mainet", "test", "", "custom"] {
+            assert!(name.parse::<Network>().is_err(), "accepted '{name}'");
+        }
+    }
+
+    #[test]
+    fn validates_custom_networks() {
+        let local = Network::custom("http://127.0.0.1:3001/", "b", 1337).unwrap();
+        assert_eq!(local.exchange_url(), "http://127.0.0.1:3001/exchange");
+        assert_eq!(local.phantom_source(), "b");
+
+        assert!(Network::custom("localhost", "b", 1337).is_err());
+        assert!(Network::custom("localhost:3001", "b", 1337).is_err());
+        assert!(Network::custom("http://127.0.0.1:3001", "c", 1337).is_err());
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use alloy::primitives::Address;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use fs2::FileExt;
+
+/// Default nonce state file, relative to the working directory
+pub const DEFAULT_NONCE_FILE: &str = "nonces.json";
+
+/// Oldest nonce the exchange accepts, relative to block time: nonces must lie within
+/// (T - 2 days, T + 1 day) of the block timestamp T
+pub const NONCE_MAX_AGE: Duration = Duration::from_secs(2 * 24 * 60 * 60);
+
+/// Furthest ahead of block time the exchange accepts a nonce
+pub const NONCE_MAX_LEAD: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Hands out strictly increasing nonces per signer, persisted across restarts
+///
+/// A nonce is the current time in milliseconds, bumped past the last nonce used by the
+/// same signer, so two submissions in the same millisecond or after a backwards clock
+/// adjustment never reuse a nonce. With a state file, each nonce is handed out under an
+/// exclusive advisory lock on a `.lock` file next to it, re-reading the state first, so
+/// several processes can share one file.
+#[derive(Debug, Default)]
+pub struct NonceManager {
+    /// State file; `None` keeps nonces in memory only
+    path: Option<PathBuf>,
+    last: Mutex<BTreeMap<Address, u64>>,
+}
+
+impl NonceManager {
+    /// Nonces tracked in memory only (strictly increasing for this process)
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load (or create on first use) the nonce state file at `path`
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let last = {
+            let _lock = lock(&path)?;
+            load(&path)?
+        };
+        Ok(Self {
+            path: Some(path),
+            last: Mutex::new(last),
+        })
+    }
+
+    /// Open the state file at `NONCE_FILE` (default `nonces.json`)
+    pub fn from_env() -> Result<Self> {
+        let path = std::env::var("NONCE_FILE").unwrap_or_else(|_| DEFAULT_NONCE_FILE.into());
+        Self::open(path)
+    }
+
+    /// Last nonce used by `signer`, if any
+    pub fn last(&self, signer: Address) -> Option<u64> {
+        self.last.lock().unwrap().get(&signer).copied()
+    }
+
+    /// Next nonce for `signer` at `now_ms`; persisted before it is returned
+    pub fn next(&self, signer: Address, now_ms: u64) -> Result<u64> {
+        let mut last = self.last.lock().unwrap();
+        // Held until the new nonce is persisted; other processes may have written since
+        let _lock = match &self.path {
+            Some(path) => {
+                let lock = lock(path)?;
+                for (address, nonce) in load(path)? {
+                    let known = last.entry(address).or_default();
+                    *known = (*known).max(nonce);
+                }
+                Some(lock)
+            }
+            None => None,
+        };
+        let nonce = match last.get(&signer) {
+            Some(&previous) => now_ms.max(previous + 1),
+            None => now_ms,
+        };
+        last.insert(signer, nonce);
+        if let Some(path) = &self.path {
+            persist(path, &last)?;
+        }
+        Ok(nonce)
+    }
+}
+
+/// Take an exclusive advisory lock on the lock file next to `path`, blocking until any
+/// other holder releases it; released when the returned file is dropped
+/// Dev: the state file itself is replaced on every write, so it cannot carry the lock
+fn lock(path: &Path) -> Result<File> {
+    let lock_path = path.with_extension("json.lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("Failed to open nonce lock {}", lock_path.display()))?;
+    file.lock_exclusive()
+        .with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+    Ok(file)
+}
+
+/// Read the state at `path`, empty if it does not exist yet
+fn load(path: &Path) -> Result<BTreeMap<Address, u64>> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read nonce state {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse nonce state {}", path.display()))
+}
+
+/// Write state to a temporary file and rename it over `path`, so a crash never leaves a
+/// truncated file behind
+fn persist(path: &Path, last: &BTreeMap<Address, u64>) -> Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_vec_pretty(last)?)
+        .with_context(|| format!("Failed to write nonce state {}", tmp.display()))?;
+    fs::rename(&tmp, path)
+        .with_context(|| format!("Failed to write nonce state {}", path.display()))
+}
+
+/// Check local time against the server's `Date` header, failing if they differ by more
+/// than `max_skew`; returns the absolute skew
+/// Dev: `Date` has 1 second resolution, so skews under a second are not detectable
+pub fn check_clock_skew(
+    server_date: &str,
+    local: DateTime<Utc>,
+    max_skew: Duration,
+) -> Result<Duration> {
+    let server = DateTime::parse_from_rfc2822(server_date)
+        .with_context(|| format!("Invalid Date header '{server_date}'"))?
+        .with_timezone(&Utc);
+    let skew = (local - server).abs().to_std()?;
+    if skew > max_skew {
+        bail!(
+            "Local clock is {}s {} server time ({server}), above the {}s limit",
+            skew.as_secs(),
+            if local > server { "ahead of" } else { "behind" },
+            max_skew.as_secs()
+        );
+    }
+    Ok(skew)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+    use chrono::TimeZone;
+
+    const A: Address = address!("0x1111111111111111111111111111111111111111");
+    const B: Address = address!("0x2222222222222222222222222222222222222222");
+
+    mod nonce_manager_tests {
+        use super::*;
+
+        #[test]
+        fn strictly_increasing_per_signer() {
+            let nonces = NonceManager::in_memory();
+            assert_eq!(nonces.next(A, 1_000).unwrap(), 1_000);
+            // Same millisecond
+            assert_eq!(nonces.next(A, 1_000).unwrap(), 1_001);
+            // Clock moved backwards
+            assert_eq!(nonces.next(A, 500).unwrap(), 1_002);
+            // Clock caught up
+            assert_eq!(nonces.next(A, 2_000).unwrap(), 2_000);
+            // Independent per signer
+            assert_eq!(nonces.next(B, 1_000).unwrap(), 1_000);
+        }
+
+        #[test]
+        fn persists_across_restarts() {
+            let path = std::env::temp_dir().join(format!("aqa-nonces-{}.json", std::process::id()));
+            let _ = fs::remove_file(&path);
+
+            let nonces = NonceManager::open(&path).unwrap();
+            assert_eq!(nonces.next(A, 5_000).unwrap(), 5_000);
+            drop(nonces);
+
+            let reopened = NonceManager::open(&path).unwrap();
+            assert_eq!(reopened.last(A), Some(5_000));
+            assert_eq!(reopened.next(A, 4_000).unwrap(), 5_001);
+            fs::remove_file(&path).unwrap();
+            let _ = fs::remove_file(path.with_extension("json.lock"));
+        }
+
+        #[test]
+        fn managers_sharing_a_file_never_reuse_nonces() {
+            let path =
+                std::env::temp_dir().join(format!("aqa-nonces-shared-{}.json", std::process::id()));
+            let _ = fs::remove_file(&path);
+
+            // Each manager stands in for a separate process with its own in-memory state
+            let nonces: Vec<u64> = std::thread::scope(|scope| {
+                let workers: Vec<_> = (0..4)
+                    .map(|_| {
+                        let path = &path;
+                        scope.spawn(move || {
+                            let manager = NonceManager::open(path).unwrap();
+                            (0..25)
+                                .map(|_| manager.next(A, 1_000).unwrap())
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect();
+                workers
+                    .into_iter()
+                    .flat_map(|w| w.join().unwrap())
+                    .collect()
+            });
+
+            let mut sorted = nonces.clone();
+            sorted.sort_unstable();
+            sorted.dedup();
+            assert_eq!(sorted.len(), 100);
+            assert_eq!(NonceManager::open(&path).unwrap().last(A), Some(1_099));
+            fs::remove_file(&path).unwrap();
+            let _ = fs::remove_file(path.with_extension("json.lock"));
+        }
+    }
+
+    mod clock_skew_tests {
+        use super::*;
+
+        const SERVER_DATE: &str = "Tue, 14 Oct 2025 22:00:00 GMT";
+
+        fn at(secs: u32) -> DateTime<Utc> {
+            Utc.with_ymd_and_hms(2025, 10, 14, 22, 0, secs).unwrap()
+        }
+
+        #[test]
+        fn within_limit() {
+            let skew = check_clock_skew(SERVER_DATE, at(3), Duration::from_secs(5)).unwrap();
+            assert_eq!(skew, Duration::from_secs(3));
+        }
+
+        #[test]
+        fn beyond_limit() {
+            let err = check_clock_skew(SERVER_DATE, at(45), Duration::from_secs(30)).unwrap_err();
+            assert!(err.to_string().contains("ahead of"));
+            assert!(check_clock_skew("not a date", at(0), Duration::from_secs(30)).is_err());
+        }
+    }
+}
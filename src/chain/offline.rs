@@ -0,0 +1,296 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use alloy::primitives::{Address, B256};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+use super::network::Network;
+use super::nonce::{NONCE_MAX_AGE, NONCE_MAX_LEAD};
+use super::signer::VoteSigner;
+use super::signing::{decode_signature, l1_action_hashes, recover_l1_action_signer};
+use super::types::{ExchangeRequest, L1Action, ValidatorL1StreamAction};
+
+/// Signing parameters of the network a request was signed for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkId {
+    /// `mainnet`, `testnet` or `custom`
+    pub name: String,
+    pub phantom_source: String,
+    pub chain_id: u64,
+}
+
+impl From<&Network> for NetworkId {
+    fn from(network: &Network) -> Self {
+        let name = match network {
+            Network::Mainnet => "mainnet",
+            Network::Testnet => "testnet",
+            Network::Custom { .. } => "custom",
+        };
+        Self {
+            name: name.to_string(),
+            phantom_source: network.phantom_source().to_string(),
+            chain_id: network.chain_id(),
+        }
+    }
+}
+
+/// Signed request written by the offline `sign` command and posted by `broadcast`
+///
+/// Holds only public data (the request, its signer and network), so it can be carried
+/// from an air-gapped signing host to a broadcasting host without sharing secrets.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedRequest<'a, A = ValidatorL1StreamAction> {
+    pub network: NetworkId,
+    /// Address the request is signed by
+    pub signer: Address,
+    #[serde(borrow)]
+    pub request: ExchangeRequest<'a, A>,
+}
+
+impl<A: L1Action> SignedRequest<'_, A> {
+    /// Check the request is for `network`, is signed by `signer`, is not expired at
+    /// `now_ms` and its nonce is within the exchange's window (`NONCE_MAX_AGE` before to
+    /// `NONCE_MAX_LEAD` after `now_ms`)
+    pub fn validate(&self, network: &Network, now_ms: u64) -> Result<()> {
+        let expected = NetworkId::from(network);
+        if self.network != expected {
+            bail!(
+                "request was signed for {} (phantom source {}, chain id {}), not {network}",
+                self.network.name,
+                self.network.phantom_source,
+                self.network.chain_id
+            );
+        }
+        if self.request.action.type_tag() != A::TYPE {
+            bail!(
+                "unexpected action type '{}', expected '{}'",
+                self.request.action.type_tag(),
+                A::TYPE
+            );
+        }
+
+        let recovered = recover_l1_action_signer(
+            &self.request.action,
+            self.request.nonce,
+            &self.request.options(),
+            network,
+            &decode_signature(&self.request.signature)?,
+        )?;
+        if recovered != self.signer {
+            bail!(
+                "signature recovers to {recovered}, not the declared signer {}",
+                self.signer
+            );
+        }
+
+        if let Some(expires_after) = self.request.expires_after
+            && expires_after <= now_ms
+        {
+            bail!("request expired at {expires_after} (now {now_ms})");
+        }
+
+        let nonce = self.request.nonce;
+        if now_ms.saturating_sub(nonce) >= NONCE_MAX_AGE.as_millis() as u64 {
+            bail!(
+                "nonce {nonce} is more than {}h old (now {now_ms}); the exchange would reject it",
+                NONCE_MAX_AGE.as_secs() / 3600
+            );
+        }
+        if nonce.saturating_sub(now_ms) >= NONCE_MAX_LEAD.as_millis() as u64 {
+            bail!(
+                "nonce {nonce} is more than {}h ahead (now {now_ms}); check the signing host's clock",
+                NONCE_MAX_LEAD.as_secs() / 3600
+            );
+        }
+        Ok(())
+    }
+
+    /// Write to `<dir>/<signer>-<nonce>.json`, returning the path
+    pub fn write_to(&self, dir: &Path) -> Result<PathBuf> {
+        let path = dir.join(format!("{}-{}.json", self.signer, self.request.nonce));
+        fs::write(&path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("Failed to write signed request {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+/// Request a dry run would have posted, with the hashes it was signed over
+///
+/// `request` is the exact `/exchange` body; `actionHash` is the phantom agent's
+/// `connectionId` and `eip712Digest` the hash the signer signed.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunRequest<'a, A = ValidatorL1StreamAction> {
+    pub network: NetworkId,
+    pub exchange_url: String,
+    pub signer: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validator: Option<Address>,
+    pub action_hash: B256,
+    pub eip712_digest: B256,
+    /// Address the request's signature recovers to
+    pub recovered_signer: Address,
+    pub request: ExchangeRequest<'a, A>,
+}
+
+impl<'a, A: L1Action> DryRunRequest<'a, A> {
+    /// Describe `request`, signed by `signer` for `network`
+    pub fn new<S: VoteSigner + ?Sized>(
+        network: &Network,
+        signer: &S,
+        request: ExchangeRequest<'a, A>,
+    ) -> Result<Self> {
+        let options = request.options();
+        let (action_hash, eip712_digest) =
+            l1_action_hashes(&request.action, request.nonce, &options, network)?;
+        let recovered_signer = recover_l1_action_signer(
+            &request.action,
+            request.nonce,
+            &options,
+            network,
+            &decode_signature(&request.signature)?,
+        )?;
+        Ok(Self {
+            network: network.into(),
+            exchange_url: network.exchange_url(),
+            signer: signer.address(),
+            label: signer.label().map(str::to_string),
+            validator: signer.validator(),
+            action_hash,
+            eip712_digest,
+            recovered_signer,
+            request,
+        })
+    }
+
+    /// Write to `<dir>/<signer>-<nonce>.dry-run.json`, returning the path
+    pub fn write_to(&self, dir: &Path) -> Result<PathBuf> {
+        let path = dir.join(format!(
+            "{}-{}.dry-run.json",
+            self.signer, self.request.nonce
+        ));
+        fs::write(&path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("Failed to write dry run {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+/// Parse a signed request file's contents
+pub fn parse_signed_request<'a, A: L1Action>(contents: &'a str) -> Result<SignedRequest<'a, A>> {
+    serde_json::from_str(contents).context("failed to parse signed request")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::signing::sign_l1_action;
+    use crate::chain::types::{ActionOptions, SignatureReq};
+    use alloy::signers::local::PrivateKeySigner;
+
+    /// Well-known throwaway key (Hardhat account #0)
+    const TEST_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    const NONCE: u64 = 1_700_000_000_000;
+
+    async fn signed(network: &Network, expires_after: Option<u64>) -> String {
+        let wallet: PrivateKeySigner = TEST_KEY.parse().unwrap();
+        let action = ValidatorL1StreamAction::new("0.035");
+        let options = ActionOptions {
+            vault_address: None,
+            expires_after,
+        };
+        let signature = sign_l1_action(&wallet, network, &action, NONCE, &options)
+            .await
+            .unwrap();
+        serde_json::to_string(&SignedRequest {
+            network: network.into(),
+            signer: wallet.address(),
+            request: ExchangeRequest {
+                action,
+                nonce: NONCE,
+                signature: SignatureReq::from_signature(&signature),
+                vault_address: None,
+                expires_after,
+            },
+        })
+        .unwrap()
+    }
+
+    fn validate(contents: &str, network: &Network, now_ms: u64) -> Result<()> {
+        parse_signed_request::<ValidatorL1StreamAction>(contents)?.validate(network, now_ms)
+    }
+
+    #[tokio::test]
+    async fn validates_signed_request() {
+        let contents = signed(&Network::Mainnet, Some(NONCE + 60_000)).await;
+        validate(&contents, &Network::Mainnet, NONCE + 1_000).unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_other_network() {
+        let contents = signed(&Network::Testnet, None).await;
+        let err = validate(&contents, &Network::Mainnet, NONCE).unwrap_err();
+        assert!(err.to_string().contains("signed for testnet"));
+
+        // Relabelled network still fails signature recovery
+        let relabelled = contents
+            .replace("\"testnet\"", "\"mainnet\"")
+            .replace("\"phantomSource\":\"b\"", "\"phantomSource\":\"a\"");
+        let err = validate(&relabelled, &Network::Mainnet, NONCE).unwrap_err();
+        assert!(err.to_string().contains("declared signer"));
+    }
+
+    #[tokio::test]
+    async fn describes_dry_run_request() {
+        let contents = signed(&Network::Testnet, None).await;
+        let signed = parse_signed_request::<ValidatorL1StreamAction>(&contents).unwrap();
+        let wallet: PrivateKeySigner = TEST_KEY.parse().unwrap();
+        let body = serde_json::to_value(&signed.request).unwrap();
+
+        let dry_run = DryRunRequest::new(&Network::Testnet, &wallet, signed.request).unwrap();
+        assert_eq!(dry_run.recovered_signer, wallet.address());
+        assert_eq!(
+            dry_run.exchange_url,
+            "https://api.hyperliquid-testnet.xyz/exchange"
+        );
+
+        // The digest is what the key signed; the request is the exact body
+        let signature = wallet.sign_hash(dry_run.eip712_digest).await.unwrap();
+        assert_eq!(
+            decode_signature(&dry_run.request.signature).unwrap(),
+            signature
+        );
+        let json = serde_json::to_value(&dry_run).unwrap();
+        assert_eq!(json["request"], body);
+        assert_eq!(json["actionHash"], dry_run.action_hash.to_string());
+        assert!(json.get("label").is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_tampered_and_expired_requests() {
+        let contents = signed(&Network::Mainnet, Some(NONCE + 60_000)).await;
+        let tampered = contents.replace("0.035", "0.099");
+        assert!(validate(&tampered, &Network::Mainnet, NONCE).is_err());
+
+        let err = validate(&contents, &Network::Mainnet, NONCE + 60_000).unwrap_err();
+        assert!(err.to_string().contains("expired"));
+    }
+
+    #[tokio::test]
+    async fn rejects_nonces_outside_exchange_window() {
+        let contents = signed(&Network::Mainnet, None).await;
+        let day_ms = 24 * 60 * 60 * 1000;
+        validate(&contents, &Network::Mainnet, NONCE + 2 * day_ms - 1).unwrap();
+        validate(&contents, &Network::Mainnet, NONCE - day_ms + 1).unwrap();
+
+        let err = validate(&contents, &Network::Mainnet, NONCE + 2 * day_ms).unwrap_err();
+        assert!(err.to_string().contains("48h old"), "{err}");
+        let err = validate(&contents, &Network::Mainnet, NONCE - day_ms).unwrap_err();
+        assert!(err.to_string().contains("24h ahead"), "{err}");
+    }
+}
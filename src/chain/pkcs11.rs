@@ -0,0 +1,354 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use alloy::primitives::{Address, B256, U256};
+use alloy_signer::Signature;
+use anyhow::{Context, Result, anyhow, bail};
+use cryptoki::context::{CInitializeArgs, CInitializeFlags, Function, Pkcs11};
+use cryptoki::error::{Error, RvError};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::types::AuthPin;
+
+use super::signer::{SignFuture, VoteSigner};
+
+/// DER encoding of the secp256k1 curve OID (1.3.132.0.10), as found in `CKA_EC_PARAMS`
+pub const SECP256K1_EC_PARAMS: [u8; 7] = [0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x0a];
+
+/// Token and keys to sign with through a PKCS#11 module
+#[derive(Clone)]
+pub struct Pkcs11Config {
+    /// Path to the PKCS#11 module (e.g., `/usr/lib/softhsm/libsofthsm2.so`)
+    pub module: PathBuf,
+    /// Label of the token holding the keys
+    pub token_label: String,
+    /// User PIN of the token
+    pub pin: String,
+    /// `CKA_LABEL` of each secp256k1 key; one signer per key
+    pub key_labels: Vec<String>,
+}
+
+impl std::fmt::Debug for Pkcs11Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pkcs11Config")
+            .field("module", &self.module)
+            .field("token_label", &self.token_label)
+            .field("key_labels", &self.key_labels)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Pkcs11Config {
+    /// Config from `PKCS11_MODULE`, `PKCS11_TOKEN_LABEL`, `PKCS11_KEY_LABELS` (comma-separated)
+    /// and `PKCS11_PIN_FILE` or `PKCS11_PIN`; `None` if `PKCS11_MODULE` is not set
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(module) = env::var("PKCS11_MODULE") else {
+            return Ok(None);
+        };
+        let pin = match (env::var("PKCS11_PIN_FILE"), env::var("PKCS11_PIN")) {
+            (Ok(path), Err(_)) => fs::read_to_string(path.trim())
+                .with_context(|| format!("Failed to read PKCS#11 PIN file {path}"))?
+                .trim_end_matches(['\r', '\n'])
+                .to_string(),
+            (Err(_), Ok(pin)) => pin,
+            (Ok(_), Ok(_)) => bail!("Set only one of PKCS11_PIN_FILE and PKCS11_PIN"),
+            (Err(_), Err(_)) => bail!("PKCS11_MODULE requires PKCS11_PIN_FILE or PKCS11_PIN"),
+        };
+        Self::parse(
+            &module,
+            env::var("PKCS11_TOKEN_LABEL").ok().as_deref(),
+            env::var("PKCS11_KEY_LABELS").ok().as_deref(),
+            pin,
+        )
+        .map(Some)
+    }
+
+    /// Build a config from a module path, token label and comma-separated key labels
+    pub fn parse(
+        module: &str,
+        token_label: Option<&str>,
+        key_labels: Option<&str>,
+        pin: String,
+    ) -> Result<Self> {
+        let token_label = token_label
+            .map(str::trim)
+            .filter(|label| !label.is_empty())
+            .context("PKCS11_MODULE requires PKCS11_TOKEN_LABEL")?;
+        let key_labels: Vec<String> = key_labels
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|label| !label.is_empty())
+            .map(str::to_string)
+            .collect();
+        if key_labels.is_empty() {
+            bail!("PKCS11_MODULE requires PKCS11_KEY_LABELS");
+        }
+        Ok(Self {
+            module: module.trim().into(),
+            token_label: token_label.to_string(),
+            pin,
+            key_labels,
+        })
+    }
+}
+
+/// secp256k1 key held in a PKCS#11 token (HSM, SoftHSM, ...)
+///
+/// The token computes raw ECDSA (`CKM_ECDSA`) over the payload hash; the signature is
+/// normalized to low-s and given the recovery id that recovers to the key's address.
+#[derive(Debug)]
+pub struct Pkcs11Signer {
+    label: String,
+    address: Address,
+    key: ObjectHandle,
+    session: Arc<Mutex<Session>>,
+}
+
+/// Logged-in session on a PKCS#11 token, shared by every signer using one of its keys
+/// Dev: PKCS#11 login state is per token, so a second session on the same token in one
+/// process would fail to log in; open each token once and take all its signers from it
+#[derive(Debug, Clone)]
+pub struct Pkcs11Token {
+    label: String,
+    session: Arc<Mutex<Session>>,
+}
+
+impl Pkcs11Token {
+    /// Load `module`, find the token labelled `token_label` and log in to it with `pin`
+    pub fn open(module: &Path, token_label: &str, pin: &str) -> Result<Self> {
+        let pkcs11 = Pkcs11::new(module)
+            .with_context(|| format!("Failed to load PKCS#11 module {}", module.display()))?;
+        pkcs11
+            .initialize(CInitializeArgs::new(CInitializeFlags::OS_LOCKING_OK))
+            .or_else(|e| match e {
+                Error::Pkcs11(RvError::CryptokiAlreadyInitialized, Function::Initialize) => Ok(()),
+                e => Err(e),
+            })
+            .context("Failed to initialize PKCS#11 module")?;
+
+        let mut slot = None;
+        for candidate in pkcs11.get_slots_with_initialized_token()? {
+            if pkcs11.get_token_info(candidate)?.label() == token_label {
+                slot = Some(candidate);
+                break;
+            }
+        }
+        let slot = slot.with_context(|| format!("No PKCS#11 token labelled '{token_label}'"))?;
+
+        // Another session of this process may already be logged in to the token
+        let session = pkcs11.open_ro_session(slot)?;
+        session
+            .login(UserType::User, Some(&AuthPin::new(pin.into())))
+            .or_else(|e| match e {
+                Error::Pkcs11(RvError::UserAlreadyLoggedIn, Function::Login) => Ok(()),
+                e => Err(e),
+            })
+            .with_context(|| format!("Failed to log in to PKCS#11 token '{token_label}'"))?;
+
+        Ok(Self {
+            label: token_label.to_string(),
+            session: Arc::new(Mutex::new(session)),
+        })
+    }
+
+    /// Signer for the secp256k1 key labelled `key_label` on this token
+    pub fn signer(&self, key_label: &str) -> Result<Pkcs11Signer> {
+        let session = self
+            .session
+            .lock()
+            .map_err(|_| anyhow!("PKCS#11 session lock poisoned"))?;
+        let (key, address) = find_key(&session, key_label)
+            .with_context(|| format!("PKCS#11 key '{key_label}' on token '{}'", self.label))?;
+        Ok(Pkcs11Signer {
+            label: key_label.to_string(),
+            address,
+            key,
+            session: self.session.clone(),
+        })
+    }
+}
+
+/// Open a logged-in session on the configured token and look up each key
+/// Dev: signers share the session; PKCS#11 sessions must not be used concurrently
+pub fn open_pkcs11_signers(config: &Pkcs11Config) -> Result<Vec<Pkcs11Signer>> {
+    let token = Pkcs11Token::open(&config.module, &config.token_label, &config.pin)?;
+    config
+        .key_labels
+        .iter()
+        .map(|label| token.signer(label))
+        .collect()
+}
+
+/// Find the secp256k1 private key labelled `label` and derive its address from the
+/// matching public key
+fn find_key(session: &Session, label: &str) -> Result<(ObjectHandle, Address)> {
+    let find = |class| -> Result<ObjectHandle> {
+        let mut objects = session.find_objects(&[
+            Attribute::Class(class),
+            Attribute::KeyType(KeyType::EC),
+            Attribute::Label(label.as_bytes().to_vec()),
+        ])?;
+        match objects.len() {
+            1 => Ok(objects.remove(0)),
+            0 => bail!("no EC {class} found"),
+            n => bail!("{n} EC {class} objects share the label"),
+        }
+    };
+    let private_key = find(ObjectClass::PRIVATE_KEY)?;
+    let public_key = find(ObjectClass::PUBLIC_KEY)?;
+
+    let mut params = None;
+    let mut point = None;
+    for attribute in session.get_attributes(
+        public_key,
+        &[AttributeType::EcParams, AttributeType::EcPoint],
+    )? {
+        match attribute {
+            Attribute::EcParams(bytes) => params = Some(bytes),
+            Attribute::EcPoint(bytes) => point = Some(bytes),
+            _ => {}
+        }
+    }
+    if params.as_deref() != Some(&SECP256K1_EC_PARAMS[..]) {
+        bail!("key is not on the secp256k1 curve");
+    }
+    let address = address_from_ec_point(&point.context("public key has no EC point")?)?;
+    Ok((private_key, address))
+}
+
+/// Address of a `CKA_EC_POINT` value: an uncompressed point, either raw or wrapped in a
+/// DER OCTET STRING (as the PKCS#11 spec requires)
+pub fn address_from_ec_point(point: &[u8]) -> Result<Address> {
+    let raw = match point {
+        [0x04, 0x41, rest @ ..] if rest.len() == 65 => rest,
+        raw if raw.len() == 65 => raw,
+        _ => bail!("unsupported EC point encoding ({} bytes)", point.len()),
+    };
+    if raw[0] != 0x04 {
+        bail!("EC point is not uncompressed");
+    }
+    Ok(Address::from_raw_public_key(&raw[1..]))
+}
+
+/// Turn a raw `r ‖ s` ECDSA signature of `hash` into a low-s recoverable signature by
+/// `address`
+pub fn recoverable_signature(raw: &[u8], hash: &B256, address: Address) -> Result<Signature> {
+    if raw.len() != 64 {
+        bail!(
+            "expected a 64-byte ECDSA signature, got {} bytes",
+            raw.len()
+        );
+    }
+    let (r, s) = raw.split_at(32);
+    let signature = Signature::new(U256::from_be_slice(r), U256::from_be_slice(s), false);
+    let signature = signature.normalize_s().unwrap_or(signature);
+
+    // Dev: the token does not report the recovery id; try both parities
+    [false, true]
+        .map(|parity| signature.with_parity(parity))
+        .into_iter()
+        .find(|candidate| candidate.recover_address_from_prehash(hash).ok() == Some(address))
+        .ok_or_else(|| anyhow!("PKCS#11 signature does not recover to {address}"))
+}
+
+impl VoteSigner for Pkcs11Signer {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    /// `CKA_LABEL` of the key
+    fn label(&self) -> Option<&str> {
+        Some(&self.label)
+    }
+
+    fn sign_hash(&self, hash: B256) -> SignFuture<'_> {
+        Box::pin(async move {
+            let (session, key) = (self.session.clone(), self.key);
+            let raw = tokio::task::spawn_blocking(move || {
+                let session = session
+                    .lock()
+                    .map_err(|_| anyhow!("PKCS#11 session lock poisoned"))?;
+                session
+                    .sign(&Mechanism::Ecdsa, key, hash.as_slice())
+                    .context("PKCS#11 signing failed")
+            })
+            .await??;
+            recoverable_signature(&raw, &hash, self.address)
+                .with_context(|| format!("PKCS#11 key '{}'", self.label))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::signers::local::PrivateKeySigner;
+    use alloy_signer::SignerSync;
+
+    /// Well-known throwaway key (Hardhat account #0)
+    const TEST_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    #[test]
+    fn derives_address_from_ec_point() {
+        let wallet: PrivateKeySigner = TEST_KEY.parse().unwrap();
+        let point = wallet
+            .credential()
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec();
+        assert_eq!(address_from_ec_point(&point).unwrap(), wallet.address());
+
+        let der = [&[0x04, 0x41][..], &point].concat();
+        assert_eq!(address_from_ec_point(&der).unwrap(), wallet.address());
+
+        assert!(address_from_ec_point(&point[..33]).is_err());
+    }
+
+    #[test]
+    fn recovers_parity_and_normalizes_s() {
+        let wallet: PrivateKeySigner = TEST_KEY.parse().unwrap();
+        let hash = B256::repeat_byte(3);
+        let expected = wallet.sign_hash_sync(&hash).unwrap();
+        let r = expected.r().to_be_bytes::<32>();
+
+        // Low-s signature without its recovery id
+        let raw = [r, expected.s().to_be_bytes::<32>()].concat();
+        let signature = recoverable_signature(&raw, &hash, wallet.address()).unwrap();
+        assert_eq!(signature, expected);
+
+        // High-s form of the same signature, as an HSM may return
+        let high_s = secp256k1_order() - expected.s();
+        let raw = [r, high_s.to_be_bytes::<32>()].concat();
+        let signature = recoverable_signature(&raw, &hash, wallet.address()).unwrap();
+        assert_eq!(signature, expected);
+
+        let other = Address::repeat_byte(1);
+        assert!(recoverable_signature(&raw, &hash, other).is_err());
+        assert!(recoverable_signature(&raw[..63], &hash, wallet.address()).is_err());
+    }
+
+    fn secp256k1_order() -> U256 {
+        "0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_config() {
+        let config =
+            Pkcs11Config::parse("/lib/softhsm2.so", Some("aqa"), Some("a, b"), "1234".into())
+                .unwrap();
+        assert_eq!(config.key_labels, ["a", "b"]);
+        assert!(!format!("{config:?}").contains("1234"));
+
+        assert!(Pkcs11Config::parse("/lib/softhsm2.so", None, Some("a"), String::new()).is_err());
+        assert!(
+            Pkcs11Config::parse("/lib/softhsm2.so", Some("aqa"), Some(" "), String::new()).is_err()
+        );
+    }
+}
@@ -0,0 +1,466 @@
+use std::env;
+use std::fmt;
+use std::sync::Arc;
+
+use alloy::primitives::Address;
+use anyhow::{Result, bail};
+use serde::Deserialize;
+
+use super::api::HyperliquidClient;
+use super::signer::{VoteSigner, signer_name};
+
+/// Single entry of the `validatorSummaries` info response
+/// Dev: unknown fields (stake, commission, ...) are ignored
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorSummary {
+    pub validator: Address,
+    /// Key the validator signs consensus messages and votes with
+    pub signer: Address,
+    #[serde(default)]
+    pub name: String,
+    pub is_active: bool,
+    #[serde(default)]
+    pub is_jailed: bool,
+}
+
+impl ValidatorSummary {
+    /// Active and not jailed, so its votes count
+    pub fn is_voting(&self) -> bool {
+        self.is_active && !self.is_jailed
+    }
+}
+
+/// `userRole` info response; agents carry the user that approved them
+#[derive(Debug, Deserialize)]
+#[serde(tag = "role", rename_all = "camelCase")]
+pub enum UserRole {
+    Agent {
+        data: AgentData,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AgentData {
+    pub user: Address,
+}
+
+/// How a signer is linked to its validator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignerRole {
+    /// The validator address itself
+    Validator,
+    /// The validator's registered signer key
+    SignerKey,
+    /// An agent (API wallet) approved by the validator or its signer key
+    Agent,
+}
+
+impl fmt::Display for SignerRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Validator => write!(f, "validator"),
+            Self::SignerKey => write!(f, "signer key"),
+            Self::Agent => write!(f, "agent"),
+        }
+    }
+}
+
+/// Outcome of checking a signer against the validator set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerStatus {
+    /// Linked to an active, unjailed validator
+    Authorized {
+        validator: Address,
+        name: String,
+        role: SignerRole,
+    },
+    /// Linked to a validator that is inactive or jailed
+    InactiveValidator { validator: Address, name: String },
+    /// Linked to a different validator than the one configured for it
+    OtherValidator {
+        configured: Address,
+        actual: Address,
+    },
+    /// Not the validator, signer key or an agent of any validator
+    Unauthorized,
+}
+
+impl SignerStatus {
+    pub fn is_authorized(&self) -> bool {
+        matches!(self, Self::Authorized { .. })
+    }
+}
+
+impl fmt::Display for SignerStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Authorized {
+                validator,
+                name,
+                role,
+            } => write!(
+                f,
+                "authorized as {role} of validator '{name}' ({validator})"
+            ),
+            Self::InactiveValidator { validator, name } => {
+                write!(f, "validator '{name}' ({validator}) is inactive or jailed")
+            }
+            Self::OtherValidator { configured, actual } => {
+                write!(
+                    f,
+                    "linked to validator {actual}, not the configured {configured}"
+                )
+            }
+            Self::Unauthorized => write!(f, "not authorized for any validator"),
+        }
+    }
+}
+
+/// Validator in `summaries` that `signer` (an agent of `agent_of`, if any) is linked to,
+/// with how it is linked
+pub fn linked_validator(
+    summaries: &[ValidatorSummary],
+    signer: Address,
+    agent_of: Option<Address>,
+) -> Option<(&ValidatorSummary, SignerRole)> {
+    let find = |address: Address| {
+        summaries.iter().find_map(|s| {
+            if s.validator == address {
+                Some((s, SignerRole::Validator))
+            } else if s.signer == address {
+                Some((s, SignerRole::SignerKey))
+            } else {
+                None
+            }
+        })
+    };
+    find(signer).or_else(|| {
+        agent_of
+            .and_then(find)
+            .map(|(summary, _)| (summary, SignerRole::Agent))
+    })
+}
+
+/// Check `signer` (an agent of `agent_of`, if any) against `summaries`, and against its
+/// `configured` validator if set
+pub fn check_signer(
+    summaries: &[ValidatorSummary],
+    signer: Address,
+    agent_of: Option<Address>,
+    configured: Option<Address>,
+) -> SignerStatus {
+    let Some((summary, role)) = linked_validator(summaries, signer, agent_of) else {
+        return SignerStatus::Unauthorized;
+    };
+
+    if let Some(configured) = configured
+        && configured != summary.validator
+    {
+        return SignerStatus::OtherValidator {
+            configured,
+            actual: summary.validator,
+        };
+    }
+    if !summary.is_voting() {
+        return SignerStatus::InactiveValidator {
+            validator: summary.validator,
+            name: summary.name.clone(),
+        };
+    }
+    SignerStatus::Authorized {
+        validator: summary.validator,
+        name: summary.name.clone(),
+        role,
+    }
+}
+
+/// User that approved `address` as an agent, looked up only if `address` is not itself a
+/// validator or signer key in `summaries`
+async fn agent_of_if_unlinked(
+    client: &HyperliquidClient,
+    summaries: &[ValidatorSummary],
+    address: Address,
+) -> Result<Option<Address>> {
+    let direct = summaries
+        .iter()
+        .any(|s| s.validator == address || s.signer == address);
+    if direct {
+        Ok(None)
+    } else {
+        client.agent_of(address).await
+    }
+}
+
+/// Check every signer against the validator set on `client`'s network, returning each
+/// signer's name and status in `signers` order
+/// Dev: agent lookups are only made for signers that are not a validator or signer key
+pub async fn check_signers(
+    client: &HyperliquidClient,
+    signers: &[Arc<dyn VoteSigner>],
+) -> Result<Vec<(String, SignerStatus)>> {
+    let summaries = client.validator_summaries().await?;
+    let mut statuses = Vec::with_capacity(signers.len());
+    for signer in signers {
+        let address = signer.address();
+        let agent_of = agent_of_if_unlinked(client, &summaries, address).await?;
+        let status = check_signer(&summaries, address, agent_of, signer.validator());
+        statuses.push((signer_name(signer.as_ref()), status));
+    }
+    Ok(statuses)
+}
+
+/// Validator each of `signers` votes for, in `signers` order: its configured validator,
+/// else the validator it is linked to on `client`'s network (`None` if not linked)
+/// Dev: the validator set is only queried if some signer has no configured validator
+pub async fn resolve_validators(
+    client: &HyperliquidClient,
+    signers: &[Arc<dyn VoteSigner>],
+) -> Result<Vec<Option<Address>>> {
+    let summaries = if signers.iter().all(|s| s.validator().is_some()) {
+        Vec::new()
+    } else {
+        client.validator_summaries().await?
+    };
+    let mut validators = Vec::with_capacity(signers.len());
+    for signer in signers {
+        let validator = match signer.validator() {
+            Some(validator) => Some(validator),
+            None => {
+                let address = signer.address();
+                let agent_of = agent_of_if_unlinked(client, &summaries, address).await?;
+                linked_validator(&summaries, address, agent_of).map(|(s, _)| s.validator)
+            }
+        };
+        validators.push(validator);
+    }
+    Ok(validators)
+}
+
+/// What to do when a signer fails the pre-flight check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreflightMode {
+    /// Refuse to start
+    Enforce,
+    /// Log a warning and continue
+    Warn,
+    /// Skip the check
+    Off,
+}
+
+impl PreflightMode {
+    /// Mode from `PREFLIGHT` (`enforce`, `warn` or `off`; default `enforce`)
+    pub fn from_env() -> Result<Self> {
+        match env::var("PREFLIGHT") {
+            Ok(mode) => Self::parse(&mode),
+            Err(_) => Ok(Self::Enforce),
+        }
+    }
+
+    pub fn parse(mode: &str) -> Result<Self> {
+        match mode.trim() {
+            "enforce" => Ok(Self::Enforce),
+            "warn" => Ok(Self::Warn),
+            "off" => Ok(Self::Off),
+            other => bail!("unknown PREFLIGHT '{other}' (expected enforce, warn or off)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALIDATOR: Address = Address::repeat_byte(0x01);
+    const SIGNER_KEY: Address = Address::repeat_byte(0x02);
+    const JAILED: Address = Address::repeat_byte(0x03);
+    const JAILED_SIGNER_KEY: Address = Address::repeat_byte(0x04);
+    const AGENT: Address = Address::repeat_byte(0x05);
+
+    fn summaries() -> Vec<ValidatorSummary> {
+        serde_json::from_str(&format!(
+            r#"[
+                {{"validator": "{VALIDATOR}", "signer": "{SIGNER_KEY}", "name": "Alpha",
+                  "stake": 100, "isJailed": false, "isActive": true}},
+                {{"validator": "{JAILED}", "signer": "{JAILED_SIGNER_KEY}", "name": "Beta",
+                  "stake": 100, "isJailed": true, "isActive": true}}
+            ]"#
+        ))
+        .unwrap()
+    }
+
+    mod check_signer_tests {
+        use super::*;
+
+        #[test]
+        fn authorizes_validator_signer_key_and_agents() {
+            let summaries = summaries();
+            let role = |signer, agent_of| match check_signer(&summaries, signer, agent_of, None) {
+                SignerStatus::Authorized { role, name, .. } => {
+                    assert_eq!(name, "Alpha");
+                    role
+                }
+                other => panic!("unexpected {other:?}"),
+            };
+            assert_eq!(role(VALIDATOR, None), SignerRole::Validator);
+            assert_eq!(role(SIGNER_KEY, None), SignerRole::SignerKey);
+            assert_eq!(role(AGENT, Some(VALIDATOR)), SignerRole::Agent);
+            assert_eq!(role(AGENT, Some(SIGNER_KEY)), SignerRole::Agent);
+
+            // Matching configured validator
+            assert!(
+                check_signer(&summaries, AGENT, Some(VALIDATOR), Some(VALIDATOR)).is_authorized()
+            );
+        }
+
+        #[test]
+        fn rejects_unlinked_inactive_and_other_validators() {
+            let summaries = summaries();
+            assert_eq!(
+                check_signer(&summaries, AGENT, None, None),
+                SignerStatus::Unauthorized
+            );
+            // Agent of a regular user
+            assert_eq!(
+                check_signer(&summaries, AGENT, Some(Address::repeat_byte(0x09)), None),
+                SignerStatus::Unauthorized
+            );
+            assert_eq!(
+                check_signer(&summaries, JAILED_SIGNER_KEY, None, None),
+                SignerStatus::InactiveValidator {
+                    validator: JAILED,
+                    name: "Beta".into()
+                }
+            );
+            assert_eq!(
+                check_signer(&summaries, SIGNER_KEY, None, Some(JAILED)),
+                SignerStatus::OtherValidator {
+                    configured: JAILED,
+                    actual: VALIDATOR
+                }
+            );
+        }
+    }
+
+    mod check_signers_tests {
+        use super::*;
+        use crate::chain::Network;
+        use crate::chain::signer::SignFuture;
+        use crate::mock_http::json;
+        use alloy::{primitives::B256, signers::local::PrivateKeySigner};
+
+        /// Serve JSON `bodies` in order, one per connection
+        fn serve(bodies: Vec<String>) -> String {
+            crate::mock_http::serve(bodies.iter().map(|b| json(b)).collect()).0
+        }
+
+        #[tokio::test]
+        async fn queries_agents_only_when_needed() {
+            let (key, agent, stranger) = (
+                PrivateKeySigner::random(),
+                PrivateKeySigner::random(),
+                PrivateKeySigner::random(),
+            );
+            let summaries = format!(
+                r#"[{{"validator": "{VALIDATOR}", "signer": "{}", "name": "Alpha",
+                      "isJailed": false, "isActive": true}}]"#,
+                key.address()
+            );
+            let url = serve(vec![
+                summaries,
+                format!(r#"{{"role": "agent", "data": {{"user": "{VALIDATOR}"}}}}"#),
+                r#"{"role": "user"}"#.to_string(),
+            ]);
+            let client = HyperliquidClient::new(Network::custom(&url, "b", 1337).unwrap());
+            let signers: Vec<Arc<dyn VoteSigner>> =
+                vec![Arc::new(key), Arc::new(agent), Arc::new(stranger)];
+
+            let statuses = check_signers(&client, &signers).await.unwrap();
+            assert!(matches!(
+                statuses[0].1,
+                SignerStatus::Authorized {
+                    role: SignerRole::SignerKey,
+                    ..
+                }
+            ));
+            assert!(matches!(
+                statuses[1].1,
+                SignerStatus::Authorized {
+                    role: SignerRole::Agent,
+                    ..
+                }
+            ));
+            assert_eq!(statuses[2].1, SignerStatus::Unauthorized);
+            assert_eq!(statuses[2].0, signers[2].address().to_string());
+        }
+
+        #[tokio::test]
+        async fn resolves_configured_and_linked_validators() {
+            struct Configured(PrivateKeySigner);
+            impl VoteSigner for Configured {
+                fn address(&self) -> Address {
+                    self.0.address()
+                }
+                fn validator(&self) -> Option<Address> {
+                    Some(JAILED)
+                }
+                fn sign_hash(&self, hash: B256) -> SignFuture<'_> {
+                    VoteSigner::sign_hash(&self.0, hash)
+                }
+            }
+
+            let (key, agent) = (PrivateKeySigner::random(), PrivateKeySigner::random());
+            let url = serve(vec![
+                format!(
+                    r#"[{{"validator": "{VALIDATOR}", "signer": "{}", "name": "Alpha",
+                          "isJailed": false, "isActive": true}}]"#,
+                    key.address()
+                ),
+                format!(
+                    r#"{{"role": "agent", "data": {{"user": "{}"}}}}"#,
+                    key.address()
+                ),
+            ]);
+            let client = HyperliquidClient::new(Network::custom(&url, "b", 1337).unwrap());
+            let signers: Vec<Arc<dyn VoteSigner>> = vec![
+                Arc::new(Configured(PrivateKeySigner::random())),
+                Arc::new(key),
+                Arc::new(agent),
+            ];
+
+            let validators = resolve_validators(&client, &signers).await.unwrap();
+            assert_eq!(validators, [Some(JAILED), Some(VALIDATOR), Some(VALIDATOR)]);
+
+            // Only configured signers: no queries at all
+            let unreachable =
+                HyperliquidClient::new(Network::custom("http://127.0.0.1:1", "b", 1337).unwrap());
+            let validators = resolve_validators(&unreachable, &signers[..1])
+                .await
+                .unwrap();
+            assert_eq!(validators, [Some(JAILED)]);
+        }
+    }
+
+    #[test]
+    fn parses_user_roles() {
+        let role: UserRole = serde_json::from_str(&format!(
+            r#"{{"role": "agent", "data": {{"user": "{VALIDATOR}"}}}}"#
+        ))
+        .unwrap();
+        assert!(matches!(role, UserRole::Agent { data } if data.user == VALIDATOR));
+        for other in [r#"{"role": "user"}"#, r#"{"role": "missing"}"#] {
+            assert!(matches!(
+                serde_json::from_str::<UserRole>(other).unwrap(),
+                UserRole::Other
+            ));
+        }
+    }
+
+    #[test]
+    fn parses_modes() {
+        assert_eq!(PreflightMode::parse("warn").unwrap(), PreflightMode::Warn);
+        assert!(PreflightMode::parse("enforced").is_err());
+    }
+}
@@ -0,0 +1,259 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::time::Duration;
+
+use alloy::{
+    hex,
+    primitives::{Address, B256},
+    signers::local::PrivateKeySigner,
+};
+use alloy_signer::{Signature, SignerSync};
+use anyhow::{Context, Result, anyhow, bail};
+use reqwest::{Client, Url};
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixStream;
+
+/// Pending signature from a `VoteSigner`
+pub type SignFuture<'a> = Pin<Box<dyn Future<Output = Result<Signature>> + Send + 'a>>;
+
+/// Signs L1 action payload hashes (EIP-712 signing hashes) for a single address
+pub trait VoteSigner: Send + Sync {
+    /// Address signatures recover to
+    fn address(&self) -> Address;
+
+    /// Operator-assigned name, if any, used in logs
+    fn label(&self) -> Option<&str> {
+        None
+    }
+
+    /// Validator the signer votes for, if configured
+    fn validator(&self) -> Option<Address> {
+        None
+    }
+
+    /// Sign the 32-byte `hash` as-is (no prefixing or rehashing)
+    fn sign_hash(&self, hash: B256) -> SignFuture<'_>;
+}
+
+/// Name of `signer` for logs: `label (address)`, or the address if unlabelled
+pub fn signer_name<S: VoteSigner + ?Sized>(signer: &S) -> String {
+    match signer.label() {
+        Some(label) => format!("{label} ({})", signer.address()),
+        None => signer.address().to_string(),
+    }
+}
+
+/// Local key held in memory
+impl VoteSigner for PrivateKeySigner {
+    fn address(&self) -> Address {
+        PrivateKeySigner::address(self)
+    }
+
+    fn sign_hash(&self, hash: B256) -> SignFuture<'_> {
+        Box::pin(async move { Ok(self.sign_hash_sync(&hash)?) })
+    }
+}
+
+/// Timeout of a single remote signing request
+const REMOTE_SIGNER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where a remote signing service listens
+#[derive(Debug, Clone)]
+pub enum RemoteEndpoint {
+    /// Base URL of an HTTP(S) service
+    Http(Url),
+    /// Unix domain socket serving HTTP
+    Unix(PathBuf),
+}
+
+impl RemoteEndpoint {
+    /// Parse `http(s)://host[:port][/base]` or `unix:/path/to/socket` (also `unix:///path`)
+    pub fn parse(endpoint: &str) -> Result<Self> {
+        let endpoint = endpoint.trim();
+        if let Some(path) = endpoint.strip_prefix("unix:") {
+            let path = path.trim_start_matches("//");
+            if !path.starts_with('/') {
+                bail!("Unix socket path in '{endpoint}' must be absolute");
+            }
+            return Ok(Self::Unix(path.into()));
+        }
+        let url = Url::parse(endpoint)
+            .with_context(|| format!("Invalid remote signer URL '{endpoint}'"))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            bail!("Remote signer URL '{endpoint}' must be http(s):// or unix:");
+        }
+        Ok(Self::Http(url))
+    }
+}
+
+/// Signing service holding the key for `address` (web3signer-style)
+///
+/// Protocol: `POST /api/v1/eth1/sign/<address>` with `{"data": "0x<32-byte hash>"}`,
+/// answered with the 65-byte `r ‖ s ‖ v` signature as hex (plain text or a JSON string).
+/// Every returned signature is checked to recover to `address`.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    address: Address,
+    endpoint: RemoteEndpoint,
+    http: Client,
+}
+
+impl RemoteSigner {
+    pub fn new(address: Address, endpoint: RemoteEndpoint) -> Self {
+        let http = Client::builder()
+            .timeout(REMOTE_SIGNER_TIMEOUT)
+            .build()
+            .expect("Failed to build HTTP client");
+        Self {
+            address,
+            endpoint,
+            http,
+        }
+    }
+
+    /// Request path for this signer's address
+    fn path(&self) -> String {
+        format!("/api/v1/eth1/sign/{}", self.address)
+    }
+
+    /// Send a signing request, returning the status code and response body
+    async fn request(&self, body: String) -> Result<(u16, String)> {
+        match &self.endpoint {
+            RemoteEndpoint::Http(base) => {
+                let url = format!("{}{}", base.as_str().trim_end_matches('/'), self.path());
+                let resp = self
+                    .http
+                    .post(&url)
+                    .header("Content-Type", "application/json")
+                    .body(body)
+                    .send()
+                    .await
+                    .with_context(|| format!("Remote signer request to {url} failed"))?;
+                Ok((resp.status().as_u16(), resp.text().await?))
+            }
+            RemoteEndpoint::Unix(socket) => tokio::time::timeout(
+                REMOTE_SIGNER_TIMEOUT,
+                post_unix(socket, &self.path(), &body),
+            )
+            .await
+            .map_err(|_| anyhow!("Remote signer request timed out"))?
+            .with_context(|| format!("Remote signer request to {} failed", socket.display())),
+        }
+    }
+}
+
+impl VoteSigner for RemoteSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn sign_hash(&self, hash: B256) -> SignFuture<'_> {
+        Box::pin(async move {
+            let body = json!({ "data": hash.to_string() }).to_string();
+            let (status, response) = self.request(body).await?;
+            if !(200..300).contains(&status) {
+                bail!("Remote signer returned HTTP {status}: {}", response.trim());
+            }
+            let signature = parse_signature(&response)?;
+
+            // Never trust the service to have used the right key
+            let recovered = signature
+                .recover_address_from_prehash(&hash)
+                .context("Remote signature does not recover")?;
+            if recovered != self.address {
+                bail!(
+                    "Remote signer signed with {recovered}, expected {}",
+                    self.address
+                );
+            }
+            Ok(signature)
+        })
+    }
+}
+
+/// Parse a hex `r ‖ s ‖ v` signature, optionally JSON-quoted; `v` may be 0/1 or 27/28
+pub fn parse_signature(response: &str) -> Result<Signature> {
+    let response = response.trim();
+    let hex_signature = match serde_json::from_str::<String>(response) {
+        Ok(quoted) => quoted,
+        Err(_) => response.to_string(),
+    };
+    let bytes = hex::decode(hex_signature.trim())
+        .with_context(|| format!("Invalid remote signature '{hex_signature}'"))?;
+    Signature::from_raw(&bytes).map_err(|e| anyhow!("Invalid remote signature: {e}"))
+}
+
+/// Minimal HTTP/1.1 `POST` over a Unix socket, reading the response until the server
+/// closes the connection
+/// Dev: reqwest has no Unix socket transport; chunked responses are not supported
+async fn post_unix(socket: &std::path::Path, path: &str, body: &str) -> Result<(u16, String)> {
+    let mut stream = UnixStream::connect(socket).await?;
+    let request = format!(
+        "POST {path} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(request.as_bytes()).await?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+    let response = String::from_utf8(response).context("Remote signer response is not UTF-8")?;
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .context("Malformed remote signer response")?;
+    if head.to_lowercase().contains("transfer-encoding: chunked") {
+        bail!("Chunked remote signer responses are not supported");
+    }
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .context("Malformed remote signer status line")?;
+    Ok((status, body.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Well-known throwaway key (Hardhat account #0)
+    const TEST_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    #[test]
+    fn parses_endpoints() {
+        assert!(matches!(
+            RemoteEndpoint::parse("http://127.0.0.1:9000").unwrap(),
+            RemoteEndpoint::Http(_)
+        ));
+        for unix in ["unix:/run/signer.sock", "unix:///run/signer.sock"] {
+            match RemoteEndpoint::parse(unix).unwrap() {
+                RemoteEndpoint::Unix(path) => assert_eq!(path, PathBuf::from("/run/signer.sock")),
+                other => panic!("unexpected {other:?}"),
+            }
+        }
+        assert!(RemoteEndpoint::parse("unix:relative.sock").is_err());
+        assert!(RemoteEndpoint::parse("localhost:9000").is_err());
+    }
+
+    #[test]
+    fn parses_signatures() {
+        let wallet: PrivateKeySigner = TEST_KEY.parse().unwrap();
+        let hash = B256::repeat_byte(7);
+        let signature = wallet.sign_hash_sync(&hash).unwrap();
+        let encoded = hex::encode_prefixed(signature.as_bytes());
+
+        assert_eq!(parse_signature(&encoded).unwrap(), signature);
+        assert_eq!(
+            parse_signature(&format!("\"{encoded}\"\n")).unwrap(),
+            signature
+        );
+
+        // `v` of 0/1 instead of 27/28
+        let mut raw = signature.as_bytes();
+        raw[64] -= 27;
+        assert_eq!(parse_signature(&hex::encode(raw)).unwrap(), signature);
+
+        assert!(parse_signature("0x1234").is_err());
+    }
+}
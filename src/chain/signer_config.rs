@@ -0,0 +1,379 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use alloy::{primitives::Address, signers::local::PrivateKeySigner};
+use anyhow::{Context, Result, bail};
+use log::info;
+use serde::Deserialize;
+
+use super::keystore::{PasswordSource, decrypt_keystore, passwords_for};
+use super::network::Network;
+use super::offline::NetworkId;
+use super::pkcs11::Pkcs11Token;
+use super::signer::{RemoteEndpoint, RemoteSigner, SignFuture, VoteSigner, signer_name};
+
+/// Environment variable pointing to a signer configuration file
+pub const SIGNERS_FILE_ENV: &str = "PUBLISHER_SIGNERS_FILE";
+
+/// Network names a signer can be enabled for
+const NETWORK_NAMES: [&str; 3] = ["mainnet", "testnet", "custom"];
+
+/// Where a configured signer's key lives
+/// Dev: secrets are never inlined; keys and PINs are read from environment variables or files
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase", deny_unknown_fields)]
+pub enum SignerSource {
+    /// Private key held in the named environment variable
+    #[serde(rename_all = "camelCase")]
+    PrivateKey { key_env: String },
+    /// Encrypted JSON keystore with its password file
+    #[serde(rename_all = "camelCase")]
+    Keystore {
+        path: PathBuf,
+        password_file: PathBuf,
+    },
+    /// secp256k1 key in a PKCS#11 token
+    #[serde(rename_all = "camelCase")]
+    Pkcs11 {
+        module: PathBuf,
+        token_label: String,
+        key_label: String,
+        pin_file: PathBuf,
+    },
+    /// Remote signing service holding the key for `address`
+    Remote { url: String, address: Address },
+}
+
+/// One signer in a signer configuration file
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SignerEntry {
+    /// Unique name used in logs
+    pub label: String,
+    /// Validator this signer votes for
+    pub validator: Address,
+    /// Networks (`mainnet`, `testnet`, `custom`) the signer votes on
+    pub networks: Vec<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub source: SignerSource,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl SignerEntry {
+    /// Whether the signer is enabled and votes on `network`
+    pub fn is_active(&self, network: &Network) -> bool {
+        self.enabled && self.networks.contains(&NetworkId::from(network).name)
+    }
+
+    /// Load the signer's key, opening PKCS#11 tokens through `tokens`
+    fn load(&self, tokens: &mut Pkcs11Tokens) -> Result<Arc<dyn VoteSigner>> {
+        let signer: Arc<dyn VoteSigner> = match &self.source {
+            SignerSource::PrivateKey { key_env } => {
+                let key = env::var(key_env).with_context(|| format!("{key_env} is not set"))?;
+                let key: PrivateKeySigner = key
+                    .trim()
+                    .parse()
+                    .with_context(|| format!("Failed to parse private key in {key_env}"))?;
+                Arc::new(key)
+            }
+            SignerSource::Keystore {
+                path,
+                password_file,
+            } => {
+                let contents = PasswordSource::File(password_file.clone()).read()?;
+                Arc::new(decrypt_keystore(path, &passwords_for(&contents, 1)?[0])?)
+            }
+            SignerSource::Pkcs11 {
+                module,
+                token_label,
+                key_label,
+                pin_file,
+            } => {
+                let pin = PasswordSource::File(pin_file.clone()).read()?;
+                let pin = passwords_for(&pin, 1)?.remove(0);
+                Arc::new(tokens.open(module, token_label, pin)?.signer(key_label)?)
+            }
+            SignerSource::Remote { url, address } => {
+                Arc::new(RemoteSigner::new(*address, RemoteEndpoint::parse(url)?))
+            }
+        };
+        Ok(signer)
+    }
+}
+
+/// PKCS#11 tokens opened while loading a signer configuration, keyed by module and token
+/// label, so signers with keys on the same token share one logged-in session
+#[derive(Default)]
+struct Pkcs11Tokens {
+    open: HashMap<(PathBuf, String), (String, Pkcs11Token)>,
+}
+
+impl Pkcs11Tokens {
+    /// Session on `token_label` in `module`, opened with `pin` on first use
+    fn open(&mut self, module: &Path, token_label: &str, pin: String) -> Result<&Pkcs11Token> {
+        let key = (module.to_path_buf(), token_label.to_string());
+        if !self.open.contains_key(&key) {
+            let token = Pkcs11Token::open(module, token_label, &pin)?;
+            self.open.insert(key.clone(), (pin.clone(), token));
+        }
+        let (opened_with, token) = &self.open[&key];
+        if *opened_with != pin {
+            bail!("PKCS#11 token '{token_label}' is configured with different PINs");
+        }
+        Ok(token)
+    }
+}
+
+/// Validated signer configuration: unique, non-empty labels with known networks
+#[derive(Debug, Clone)]
+pub struct SignerConfig {
+    entries: Vec<SignerEntry>,
+}
+
+impl SignerConfig {
+    pub fn new(entries: Vec<SignerEntry>) -> Result<Self> {
+        if entries.is_empty() {
+            bail!("at least one signer is required");
+        }
+        let mut labels = HashSet::new();
+        for entry in &entries {
+            if entry.label.trim().is_empty() {
+                bail!("signer labels must not be empty");
+            }
+            if !labels.insert(entry.label.as_str()) {
+                bail!("duplicate signer label '{}'", entry.label);
+            }
+            if entry.networks.is_empty() {
+                bail!("signer '{}' lists no networks", entry.label);
+            }
+            if let Some(network) = entry
+                .networks
+                .iter()
+                .find(|n| !NETWORK_NAMES.contains(&n.as_str()))
+            {
+                bail!(
+                    "signer '{}' has unknown network '{network}' (expected mainnet, testnet or custom)",
+                    entry.label
+                );
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    /// Load signers from a JSON array file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read signers from {}", path.display()))?;
+        let entries: Vec<SignerEntry> = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse signers in {}", path.display()))?;
+        Self::new(entries)
+    }
+
+    /// Load from `PUBLISHER_SIGNERS_FILE` if set
+    pub fn from_env() -> Result<Option<Self>> {
+        match env::var(SIGNERS_FILE_ENV) {
+            Ok(path) => Self::from_file(Path::new(path.trim())).map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    pub fn entries(&self) -> &[SignerEntry] {
+        &self.entries
+    }
+
+    /// Load the keys of every signer active on `network`, skipping the rest
+    pub fn load(&self, network: &Network) -> Result<Vec<Arc<dyn VoteSigner>>> {
+        let mut signers: Vec<Arc<dyn VoteSigner>> = Vec::new();
+        let mut tokens = Pkcs11Tokens::default();
+        for entry in &self.entries {
+            if !entry.is_active(network) {
+                let reason = if entry.enabled {
+                    "not enabled for this network"
+                } else {
+                    "disabled"
+                };
+                info!("Skipping signer '{}' ({reason})", entry.label);
+                continue;
+            }
+            let signer = entry
+                .load(&mut tokens)
+                .with_context(|| format!("Failed to load signer '{}'", entry.label))?;
+            signers.push(Arc::new(ConfiguredSigner {
+                label: entry.label.clone(),
+                validator: entry.validator,
+                signer,
+            }));
+        }
+        if signers.is_empty() {
+            bail!("No signers in {SIGNERS_FILE_ENV} are enabled for {network}");
+        }
+        Ok(signers)
+    }
+}
+
+/// Signer from a configuration file, with its label and validator
+pub struct ConfiguredSigner {
+    label: String,
+    validator: Address,
+    signer: Arc<dyn VoteSigner>,
+}
+
+impl VoteSigner for ConfiguredSigner {
+    fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    fn label(&self) -> Option<&str> {
+        Some(&self.label)
+    }
+
+    fn validator(&self) -> Option<Address> {
+        Some(self.validator)
+    }
+
+    fn sign_hash(&self, hash: alloy::primitives::B256) -> SignFuture<'_> {
+        self.signer.sign_hash(hash)
+    }
+}
+
+/// Reject signers sharing an address, which would vote twice
+pub fn reject_duplicates(signers: &[Arc<dyn VoteSigner>]) -> Result<()> {
+    let mut seen: HashMap<Address, String> = HashMap::new();
+    for signer in signers {
+        let name = signer_name(signer.as_ref());
+        if let Some(first) = seen.insert(signer.address(), name.clone()) {
+            bail!("Duplicate signer: {name} has the same address as {first}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_A: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const VALIDATOR: &str = "0x2222222222222222222222222222222222222222";
+
+    fn remote(label: &str, address: &str, networks: &str, enabled: bool) -> String {
+        format!(
+            r#"{{"label": "{label}", "validator": "{VALIDATOR}", "networks": [{networks}],
+                "enabled": {enabled},
+                "source": {{"kind": "remote", "url": "unix:/run/signer.sock", "address": "{address}"}}}}"#
+        )
+    }
+
+    fn parse(entries: &[String]) -> Result<SignerConfig> {
+        SignerConfig::new(serde_json::from_str(&format!("[{}]", entries.join(",")))?)
+    }
+
+    mod signer_config_tests {
+        use super::*;
+
+        #[test]
+        fn parses_all_sources() {
+            let json = format!(
+                r#"[
+                    {{"label": "a", "validator": "{VALIDATOR}", "networks": ["mainnet"],
+                      "source": {{"kind": "privateKey", "keyEnv": "VALIDATOR_A_KEY"}}}},
+                    {{"label": "b", "validator": "{VALIDATOR}", "networks": ["testnet"],
+                      "source": {{"kind": "keystore", "path": "/k/b.json", "passwordFile": "/s/b"}}}},
+                    {{"label": "c", "validator": "{VALIDATOR}", "networks": ["mainnet", "testnet"],
+                      "enabled": false,
+                      "source": {{"kind": "pkcs11", "module": "/lib/softhsm2.so",
+                                  "tokenLabel": "aqa", "keyLabel": "c", "pinFile": "/s/pin"}}}}
+                ]"#
+            );
+            let config = SignerConfig::new(serde_json::from_str(&json).unwrap()).unwrap();
+            let entries = config.entries();
+            assert!(entries[0].enabled);
+            assert_eq!(
+                entries[0].source,
+                SignerSource::PrivateKey {
+                    key_env: "VALIDATOR_A_KEY".into()
+                }
+            );
+            assert!(!entries[2].enabled);
+            assert!(matches!(entries[2].source, SignerSource::Pkcs11 { .. }));
+
+            // Inline secrets and unknown fields are rejected
+            let inline = json.replace(
+                "\"keyEnv\": \"VALIDATOR_A_KEY\"",
+                &format!("\"key\": \"{KEY_A}\""),
+            );
+            assert!(serde_json::from_str::<Vec<SignerEntry>>(&inline).is_err());
+        }
+
+        #[test]
+        fn rejects_invalid_entries() {
+            let a = "0x1111111111111111111111111111111111111111";
+            assert!(parse(&[]).is_err());
+            assert!(
+                parse(&[
+                    remote("a", a, "\"mainnet\"", true),
+                    remote("a", a, "\"testnet\"", true)
+                ])
+                .is_err()
+            );
+            assert!(parse(&[remote(" ", a, "\"mainnet\"", true)]).is_err());
+            assert!(parse(&[remote("a", a, "", true)]).is_err());
+            assert!(parse(&[remote("a", a, "\"mainet\"", true)]).is_err());
+        }
+
+        #[test]
+        fn loads_active_signers_with_labels() {
+            let (a, b) = (
+                "0x1111111111111111111111111111111111111111",
+                "0x3333333333333333333333333333333333333333",
+            );
+            let config = parse(&[
+                remote("validator-a", a, "\"mainnet\"", true),
+                remote("validator-b", b, "\"mainnet\", \"testnet\"", true),
+                remote("validator-c", b, "\"mainnet\"", false),
+            ])
+            .unwrap();
+
+            let signers = config.load(&Network::Mainnet).unwrap();
+            let names: Vec<String> = signers.iter().map(|s| signer_name(s.as_ref())).collect();
+            assert_eq!(
+                names,
+                [
+                    format!("validator-a ({})", a.parse::<Address>().unwrap()),
+                    format!("validator-b ({})", b.parse::<Address>().unwrap()),
+                ]
+            );
+            assert_eq!(signers[0].validator(), Some(VALIDATOR.parse().unwrap()));
+            assert_eq!(config.load(&Network::Testnet).unwrap().len(), 1);
+
+            let only_disabled = parse(&[remote("validator-c", b, "\"mainnet\"", false)]).unwrap();
+            assert!(only_disabled.load(&Network::Mainnet).is_err());
+        }
+    }
+
+    mod reject_duplicates_tests {
+        use super::*;
+
+        #[test]
+        fn rejects_shared_addresses() {
+            let key: PrivateKeySigner = KEY_A.parse().unwrap();
+            let labelled: Arc<dyn VoteSigner> = Arc::new(ConfiguredSigner {
+                label: "validator-a".into(),
+                validator: VALIDATOR.parse().unwrap(),
+                signer: Arc::new(key.clone()),
+            });
+            let plain: Arc<dyn VoteSigner> = Arc::new(key);
+            let other: Arc<dyn VoteSigner> = Arc::new(PrivateKeySigner::random());
+
+            reject_duplicates(&[labelled.clone(), other.clone()]).unwrap();
+            let err = reject_duplicates(&[labelled, other, plain]).unwrap_err();
+            assert!(err.to_string().contains("validator-a"), "{err}");
+        }
+    }
+}
@@ -1,15 +1,19 @@
 use alloy::{
     dyn_abi::Eip712Domain,
-    primitives::{Address, B256, keccak256},
-    signers::local::PrivateKeySigner,
+    primitives::{Address, B256, U256, keccak256},
     sol,
     sol_types::{SolStruct, eip712_domain},
 };
-use alloy_signer::{Signature, SignerSync};
-use anyhow::Result;
+use alloy_signer::Signature;
+use anyhow::{Context, Result, anyhow, bail};
 use rmp_serde::to_vec_named;
+use serde::Serialize;
 
-use super::types::ValidatorL1StreamAction;
+use super::network::Network;
+use super::signer::VoteSigner;
+use super::types::{
+    ActionOptions, ExchangeRequest, L1Action, SignatureReq, ValidatorL1StreamAction,
+};
 
 sol! {
     /// `Agent` message for `l1_payload`
@@ -19,15 +23,18 @@ sol! {
     }
 }
 
-/// EIP-712 domain for HyperCore message verification
+/// EIP-712 domain for HyperCore message verification on `network` (chain id 1337 on
+/// mainnet and testnet)
 /// Ref(Python): https://github.com/hyperliquid-dex/hyperliquid-python-sdk/blob/8baad667965968a020a2bb90d0287df0922ca941/hyperliquid/utils/signing.py#L184
 /// Ref(Rust): https://github.com/hyperliquid-dex/hyperliquid-rust-sdk/blob/aac75585daf12d0a3761126cc7da7a5e035b5853/src/signature/agent.rs#L21
-const CORE_DOMAIN: Eip712Domain = eip712_domain! {
-    name: "Exchange",
-    version: "1",
-    chain_id: 1337u64,
-    verifying_contract: Address::ZERO,
-};
+fn core_domain(network: &Network) -> Eip712Domain {
+    eip712_domain! {
+        name: "Exchange",
+        version: "1",
+        chain_id: network.chain_id(),
+        verifying_contract: Address::ZERO,
+    }
+}
 
 /// Minimal re-implementation of hashing action struct using MessagePack
 ///
@@ -35,24 +42,41 @@ const CORE_DOMAIN: Eip712Domain = eip712_domain! {
 /// unlike when using raw `Value` which is represented as a `BTreeMap` which
 /// sorts alphabetically to enforce determinism; this impl. does not rely on JSON map.
 ///
-/// Ref: https://github.com/hyperliquid-dex/hyperliquid-rust-sdk/blob/aac75585daf12d0a3761126cc7da7a5e035b5853/src/exchange/exchange_client.rs#L87
-fn action_hash(action: &ValidatorL1StreamAction, nonce: u64) -> Result<B256> {
+/// Layout: msgpack(action) ‖ nonce (u64 BE) ‖ vault (0x00, or 0x01 ‖ 20-byte address)
+/// ‖ [0x00 ‖ expiresAfter (u64 BE), only when set]
+///
+/// Ref(Python): https://github.com/hyperliquid-dex/hyperliquid-python-sdk/blob/master/hyperliquid/utils/signing.py (`action_hash`)
+/// Ref(Rust): https://github.com/hyperliquid-dex/hyperliquid-rust-sdk/blob/aac75585daf12d0a3761126cc7da7a5e035b5853/src/exchange/exchange_client.rs#L87
+fn action_hash<T: Serialize>(action: &T, nonce: u64, options: &ActionOptions) -> Result<B256> {
     // Serialize action into MessagePack payload with field names
-    let mut bytes = to_vec_named(&action)?;
+    let mut bytes = to_vec_named(action)?;
 
-    // Append big-endian timestamp (nonce) and empty vault byte (0)
+    // Append big-endian timestamp (nonce)
     bytes.extend(nonce.to_be_bytes());
-    bytes.push(0);
+
+    // Append vault flag, followed by the vault address if present
+    match options.vault_address {
+        Some(vault) => {
+            bytes.push(1);
+            bytes.extend(vault.as_slice());
+        }
+        None => bytes.push(0),
+    }
+
+    // Append expiry marker and big-endian expiry, only if present
+    if let Some(expires_after) = options.expires_after {
+        bytes.push(0);
+        bytes.extend(expires_after.to_be_bytes());
+    }
 
     Ok(keccak256(bytes))
 }
 
 /// Generate L1 payload hash to sign
-fn l1_payload_hash(action_hash: B256, is_mainnet: bool) -> B256 {
+fn l1_payload_hash(action_hash: B256, network: &Network) -> B256 {
     // Setup phantom agent source
-    // Ref(Python): https://github.com/hyperliquid-dex/hyperliquid-python-sdk/blob/8baad667965968a020a2bb90d0287df0922ca941/hyperliquid/utils/signing.py#L178
     // Ref(Rust): https://github.com/hyperliquid-dex/hyperliquid-rust-sdk/blob/aac75585daf12d0a3761126cc7da7a5e035b5853/src/signature/create_signature.rs#L13
-    let phantom_src = if is_mainnet { "a" } else { "b" };
+    let phantom_src = network.phantom_source();
 
     // Prepare EIP-712 L1 message payload to sign
     // Ref: https://github.com/hyperliquid-dex/hyperliquid-python-sdk/blob/8baad667965968a020a2bb90d0287df0922ca941/hyperliquid/utils/signing.py#L182
@@ -62,22 +86,399 @@ fn l1_payload_hash(action_hash: B256, is_mainnet: bool) -> B256 {
     };
 
     // Encode EIP-712 signing hash for wallet to sign over
-    payload.eip712_signing_hash(&CORE_DOMAIN)
+    payload.eip712_signing_hash(&core_domain(network))
+}
+
+/// Action hash (the phantom agent's `connectionId`) and the EIP-712 digest signed for
+/// `action` at `nonce` (with `options`) on `network`
+pub fn l1_action_hashes<A: L1Action>(
+    action: &A,
+    nonce: u64,
+    options: &ActionOptions,
+    network: &Network,
+) -> Result<(B256, B256)> {
+    let action_hash = action_hash(action, nonce, options)?;
+    Ok((action_hash, l1_payload_hash(action_hash, network)))
+}
+
+/// Sign `action` at `nonce` (with `options`) on `network` over its L1 payload hash
+pub async fn sign_l1_action<A: L1Action, S: VoteSigner + ?Sized>(
+    signer: &S,
+    network: &Network,
+    action: &A,
+    nonce: u64,
+    options: &ActionOptions,
+) -> Result<Signature> {
+    // Prepare payload hash to sign
+    let action_hash = action_hash(action, nonce, options)?;
+    let typed_data_hash = l1_payload_hash(action_hash, network);
+
+    // Sign payload hash
+    signer.sign_hash(typed_data_hash).await
 }
 
 /// Prepares payload (given `rate`, generates `validatorL1Stream` message payload), signs over payload hash
-pub fn get_signed_vote(
-    wallet: &PrivateKeySigner,
-    is_mainnet: bool,
+pub async fn get_signed_vote<S: VoteSigner + ?Sized>(
+    signer: &S,
+    network: &Network,
     nonce: u64,
     rate: &str,
+    options: &ActionOptions,
 ) -> Result<(ValidatorL1StreamAction, Signature)> {
-    // Prepare payload hash to sign
     let action = ValidatorL1StreamAction::new(rate);
-    let action_hash = action_hash(&action, nonce)?;
-    let typed_data_hash = l1_payload_hash(action_hash, is_mainnet);
-
-    // Sign payload hash
-    let signature = wallet.sign_hash_sync(&typed_data_hash)?;
+    let signature = sign_l1_action(signer, network, &action, nonce, options).await?;
     Ok((action, signature))
 }
+
+/// Decode a wire-format signature (hex `r`, `s` and `v` of 27 or 28)
+pub fn decode_signature(signature: &SignatureReq) -> Result<Signature> {
+    let parse = |name: &str, hex: &str| {
+        let digits = hex
+            .strip_prefix("0x")
+            .ok_or_else(|| anyhow!("signature {name} must be 0x-prefixed hex"))?;
+        U256::from_str_radix(digits, 16).with_context(|| format!("invalid signature {name}"))
+    };
+    let y_parity = match signature.v {
+        27 => false,
+        28 => true,
+        v => bail!("invalid signature v {v}, expected 27 or 28"),
+    };
+    Ok(Signature::new(
+        parse("r", &signature.r)?,
+        parse("s", &signature.s)?,
+        y_parity,
+    ))
+}
+
+/// Recover the address that signed `action` at `nonce` (with `options`) on `network`
+pub fn recover_l1_action_signer<A: L1Action>(
+    action: &A,
+    nonce: u64,
+    options: &ActionOptions,
+    network: &Network,
+    signature: &Signature,
+) -> Result<Address> {
+    let typed_data_hash = l1_payload_hash(action_hash(action, nonce, options)?, network);
+    signature
+        .recover_address_from_prehash(&typed_data_hash)
+        .context("failed to recover signer from signature")
+}
+
+/// Recover the address that signed the vote `action` (see `recover_l1_action_signer`)
+pub fn recover_vote_signer(
+    action: &ValidatorL1StreamAction,
+    nonce: u64,
+    options: &ActionOptions,
+    network: &Network,
+    signature: &Signature,
+) -> Result<Address> {
+    recover_l1_action_signer(action, nonce, options, network, signature)
+}
+
+/// Verify a serialized `ExchangeRequest` (JSON) carrying an `A` action for `network` and
+/// return the recovered signer
+///
+/// Useful to audit a captured request: a signature over any other action, nonce or
+/// network recovers to a different (unrelated) address.
+pub fn verify_request<A: L1Action>(request: &str, network: &Network) -> Result<Address> {
+    let request: ExchangeRequest<A> =
+        serde_json::from_str(request).context("failed to parse ExchangeRequest")?;
+    if request.action.type_tag() != A::TYPE {
+        bail!(
+            "unexpected action type '{}', expected '{}'",
+            request.action.type_tag(),
+            A::TYPE
+        );
+    }
+    let signature = decode_signature(&request.signature)?;
+    recover_l1_action_signer(
+        &request.action,
+        request.nonce,
+        &request.options(),
+        network,
+        &signature,
+    )
+}
+
+/// Verify a serialized vote `ExchangeRequest` (see `verify_request`)
+pub fn verify_vote_request(request: &str, network: &Network) -> Result<Address> {
+    verify_request::<ValidatorL1StreamAction>(request, network)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{primitives::address, signers::local::PrivateKeySigner};
+    use alloy_signer::SignerSync;
+
+    /// Well-known throwaway key (Hardhat account #0)
+    const TEST_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    fn wallet() -> PrivateKeySigner {
+        TEST_KEY.parse().unwrap()
+    }
+
+    /// Drive a local (never pending) signing future to completion
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    /// Serialize a signed vote the same way `HyperliquidClient::submit_vote` does
+    fn signed_request(network: &Network, nonce: u64, rate: &str) -> String {
+        signed_request_with(network, nonce, rate, ActionOptions::default())
+    }
+
+    fn signed_request_with(
+        network: &Network,
+        nonce: u64,
+        rate: &str,
+        options: ActionOptions,
+    ) -> String {
+        let (action, signature) =
+            block_on(get_signed_vote(&wallet(), network, nonce, rate, &options)).unwrap();
+        serde_json::to_string(&ExchangeRequest {
+            action,
+            nonce,
+            signature: SignatureReq::from_signature(&signature),
+            vault_address: options.vault_address,
+            expires_after: options.expires_after,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn recovers_signer_of_request() {
+        for network in [Network::Mainnet, Network::Testnet] {
+            let request = signed_request(&network, 1_700_000_000_000, "0.03515319");
+            assert_eq!(
+                verify_vote_request(&request, &network).unwrap(),
+                wallet().address()
+            );
+        }
+    }
+
+    #[test]
+    fn tampering_changes_recovered_signer() {
+        let request = signed_request(&Network::Mainnet, 1_700_000_000_000, "0.03515319");
+
+        // Wrong network
+        let recovered = verify_vote_request(&request, &Network::Testnet).unwrap();
+        assert_ne!(recovered, wallet().address());
+
+        // Altered rate
+        let altered = request.replace("0.03515319", "0.09999999");
+        let recovered = verify_vote_request(&altered, &Network::Mainnet).unwrap();
+        assert_ne!(recovered, wallet().address());
+    }
+
+    #[test]
+    fn rejects_malformed_requests() {
+        let request = signed_request(&Network::Mainnet, 1, "0.03515319");
+        assert!(verify_vote_request("{}", &Network::Mainnet).is_err());
+        assert!(
+            verify_vote_request(
+                &request.replace("validatorL1Stream", "order"),
+                &Network::Mainnet
+            )
+            .is_err()
+        );
+        assert!(
+            verify_vote_request(&request.replace("\"v\":2", "\"v\":3"), &Network::Mainnet).is_err()
+        );
+    }
+
+    #[test]
+    fn options_are_covered_by_signature() {
+        let options = ActionOptions {
+            vault_address: Some(address!("0x1719884eb866cb12b2287399b15f7db5e7d775ea")),
+            expires_after: Some(1_700_000_060_000),
+        };
+        let request = signed_request_with(&Network::Mainnet, 1_700_000_000_000, "0.035", options);
+        assert!(request.contains("\"expiresAfter\":1700000060000"));
+        assert_eq!(
+            verify_vote_request(&request, &Network::Mainnet).unwrap(),
+            wallet().address()
+        );
+
+        // Extending the expiry invalidates the signature
+        let extended = request.replace("1700000060000", "1700000120000");
+        assert_ne!(
+            verify_vote_request(&extended, &Network::Mainnet).unwrap(),
+            wallet().address()
+        );
+    }
+
+    mod l1_action_tests {
+        use super::*;
+        use serde::Deserialize;
+
+        /// Stand-in for another validator-side action
+        #[derive(Debug, Serialize, Deserialize)]
+        struct ToggleAction {
+            #[serde(rename = "type")]
+            type_string: String,
+            enabled: bool,
+        }
+
+        impl L1Action for ToggleAction {
+            const TYPE: &'static str = "toggle";
+
+            fn type_tag(&self) -> &str {
+                &self.type_string
+            }
+        }
+
+        fn signed_toggle(network: &Network, nonce: u64) -> String {
+            let action = ToggleAction {
+                type_string: ToggleAction::TYPE.to_string(),
+                enabled: true,
+            };
+            let signature = block_on(sign_l1_action(
+                &wallet(),
+                network,
+                &action,
+                nonce,
+                &ActionOptions::default(),
+            ))
+            .unwrap();
+            serde_json::to_string(&ExchangeRequest {
+                action,
+                nonce,
+                signature: SignatureReq::from_signature(&signature),
+                vault_address: None,
+                expires_after: None,
+            })
+            .unwrap()
+        }
+
+        #[test]
+        fn signs_and_verifies_other_actions() {
+            let request = signed_toggle(&Network::Mainnet, 1_700_000_000_000);
+            assert_eq!(
+                verify_request::<ToggleAction>(&request, &Network::Mainnet).unwrap(),
+                wallet().address()
+            );
+
+            // Altered field
+            let altered = request.replace("true", "false");
+            assert_ne!(
+                verify_request::<ToggleAction>(&altered, &Network::Mainnet).unwrap(),
+                wallet().address()
+            );
+        }
+
+        #[test]
+        fn rejects_other_action_types() {
+            let request = signed_toggle(&Network::Mainnet, 1);
+            assert!(verify_vote_request(&request, &Network::Mainnet).is_err());
+
+            let renamed = signed_toggle(&Network::Mainnet, 1).replace("toggle", "other");
+            assert!(verify_request::<ToggleAction>(&renamed, &Network::Mainnet).is_err());
+        }
+    }
+
+    /// Golden vectors from the official SDKs
+    mod sdk_vector_tests {
+        use super::*;
+
+        /// `{"type": "dummy", "num": float_to_int_for_hashing(1000)}` from the SDK tests
+        #[derive(Serialize)]
+        struct Dummy {
+            #[serde(rename = "type")]
+            type_string: &'static str,
+            num: u64,
+        }
+
+        const DUMMY: Dummy = Dummy {
+            type_string: "dummy",
+            num: 100_000_000_000,
+        };
+
+        /// Key used throughout the SDK signing tests
+        const SDK_KEY: &str = "0x0123456789012345678901234567890123456789012345678901234567890123";
+
+        fn sign(action_hash: B256, network: &Network) -> (String, String, u64) {
+            let wallet: PrivateKeySigner = SDK_KEY.parse().unwrap();
+            let signature = wallet
+                .sign_hash_sync(&l1_payload_hash(action_hash, network))
+                .unwrap();
+            (
+                format!("0x{:x}", signature.r()),
+                format!("0x{:x}", signature.s()),
+                27 + signature.v() as u64,
+            )
+        }
+
+        /// Python SDK `test_l1_action_signing_matches`
+        #[test]
+        fn l1_action_signing_matches() {
+            let hash = action_hash(&DUMMY, 0, &ActionOptions::default()).unwrap();
+            assert_eq!(
+                sign(hash, &Network::Mainnet),
+                (
+                    "0x53749d5b30552aeb2fca34b530185976545bb22d0b3ce6f62e31be961a59298".into(),
+                    "0x755c40ba9bf05223521753995abb2f73ab3229be8ec921f350cb447e384d8ed8".into(),
+                    27
+                )
+            );
+            assert_eq!(
+                sign(hash, &Network::Testnet),
+                (
+                    "0x542af61ef1f429707e3c76c5293c80d01f74ef853e34b76efffcb57e574f9510".into(),
+                    "0x17b8b32f086e8cdede991f1e2c529f5dd5297cbe8128500e00cbaf766204a613".into(),
+                    28
+                )
+            );
+        }
+
+        /// `updateLeverage` action as the official Rust SDK serializes it
+        #[derive(Serialize)]
+        struct UpdateLeverage {
+            #[serde(rename = "type")]
+            type_string: &'static str,
+            asset: u32,
+            #[serde(rename = "isCross")]
+            is_cross: bool,
+            leverage: u32,
+        }
+
+        /// Requests captured from hyperliquid_rust_sdk 0.6.0 `ExchangeClient::update_leverage`
+        /// (10x cross on asset 0, local API so signed for testnet), without and with a vault
+        #[test]
+        fn vault_signing_matches_rust_sdk() {
+            let action = UpdateLeverage {
+                type_string: "updateLeverage",
+                asset: 0,
+                is_cross: true,
+                leverage: 10,
+            };
+
+            let hash = action_hash(&action, 1_792_344_429_439, &ActionOptions::default()).unwrap();
+            assert_eq!(
+                sign(hash, &Network::Testnet),
+                (
+                    "0x19dc805260c388c5f751054d86e9707a0e4489a4c716c1d5bf302015469d280d".into(),
+                    "0x66f0d7f8bfdad22cbfd72d197e89b4087c5cf5c9a8a3519612458eb92f4ea384".into(),
+                    27
+                )
+            );
+
+            let options = ActionOptions {
+                vault_address: Some(address!("0x1719884eb866cb12b2287399b15f7db5e7d775ea")),
+                expires_after: None,
+            };
+            let hash = action_hash(&action, 1_792_344_429_440, &options).unwrap();
+            assert_eq!(
+                sign(hash, &Network::Testnet),
+                (
+                    "0x5b01fa8edd88ce59d13b4ba0d257e408b7afb476eb691bfa9d36a0aa4f681dec".into(),
+                    "0x3897cdc2db06932be81a7526d15d6cb350e58b9435da69d5dd5cbec7631b7b66".into(),
+                    27
+                )
+            );
+        }
+    }
+}
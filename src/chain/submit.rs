@@ -0,0 +1,254 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use alloy::primitives::Address;
+use anyhow::Result;
+use log::{error, info, warn};
+use tokio::task::JoinSet;
+
+use super::api::HyperliquidClient;
+use super::error::{Disposition, ExchangeError};
+use super::signer::{VoteSigner, signer_name};
+use super::types::ActionResponse;
+use crate::sources::retry::RetryPolicy;
+
+/// Default retry policy for vote submission: 3 attempts with 1s, 2s backoff (±20%)
+pub fn default_vote_retry_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        initial_delay: Duration::from_secs(1),
+        max_delay: Duration::from_secs(8),
+        jitter: 0.2,
+    }
+}
+
+/// Vote retry policy from `VOTE_RETRY_MAX_ATTEMPTS`, `VOTE_RETRY_INITIAL_DELAY_SECS`,
+/// `VOTE_RETRY_MAX_DELAY_SECS` and `VOTE_RETRY_JITTER`
+pub fn vote_retry_policy_from_env() -> Result<RetryPolicy> {
+    RetryPolicy::from_env_prefixed("VOTE_RETRY", default_vote_retry_policy())
+}
+
+/// How to react to a submission error; failures before the request is sent (signing,
+/// nonce persistence, clock skew) are not exchange errors and are given up on
+pub fn disposition(error: &anyhow::Error) -> Disposition {
+    error
+        .downcast_ref::<ExchangeError>()
+        .map_or(Disposition::GiveUp, ExchangeError::disposition)
+}
+
+/// Whether a submission error is worth retrying (see `ExchangeError::is_transient`)
+pub fn is_transient(error: &anyhow::Error) -> bool {
+    disposition(error) == Disposition::Retry
+}
+
+/// Outcome of submitting one signer's vote
+#[derive(Debug)]
+pub struct SignerOutcome {
+    pub signer: Address,
+    /// Signer name for logs (label and address)
+    pub name: String,
+    /// Attempts made, including the first
+    pub attempts: u32,
+    /// Time from first attempt to final result
+    pub elapsed: Duration,
+    /// Exchange response, or the last error
+    pub result: Result<ActionResponse>,
+}
+
+impl SignerOutcome {
+    pub fn is_success(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Submit `signer`'s vote for `rate`, retrying transient failures per `policy`
+/// Each attempt is freshly signed with a new nonce, so a retry never replays a request
+pub async fn submit_with_retry<S: VoteSigner + ?Sized>(
+    client: &HyperliquidClient,
+    signer: &S,
+    rate: &str,
+    policy: &RetryPolicy,
+) -> SignerOutcome {
+    let name = signer_name(signer);
+    let started = Instant::now();
+    let mut attempt = 1;
+    loop {
+        let result = client.submit_vote(signer, rate).await;
+        let retry = match &result {
+            Err(e) if attempt < policy.max_attempts && is_transient(e) => {
+                let delay = policy.jittered_backoff(attempt);
+                warn!(
+                    "Vote for signer {name} failed (attempt {attempt}/{}): {e}; retrying in {:.1}s...",
+                    policy.max_attempts,
+                    delay.as_secs_f64()
+                );
+                Some(delay)
+            }
+            _ => None,
+        };
+        match retry {
+            Some(delay) => {
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            None => {
+                return SignerOutcome {
+                    signer: signer.address(),
+                    name,
+                    attempts: attempt,
+                    elapsed: started.elapsed(),
+                    result,
+                };
+            }
+        }
+    }
+}
+
+/// Submit every signer's vote concurrently through one shared `client`
+/// Outcomes are returned in `signers` order
+pub async fn submit_all(
+    client: Arc<HyperliquidClient>,
+    signers: &[Arc<dyn VoteSigner>],
+    rate: &str,
+    policy: &RetryPolicy,
+) -> Vec<SignerOutcome> {
+    let mut tasks = JoinSet::new();
+    for (idx, signer) in signers.iter().cloned().enumerate() {
+        let (client, rate, policy) = (client.clone(), rate.to_string(), *policy);
+        tasks.spawn(async move {
+            let outcome = submit_with_retry(&client, signer.as_ref(), &rate, &policy).await;
+            (idx, outcome)
+        });
+    }
+
+    let mut outcomes: Vec<Option<SignerOutcome>> = signers.iter().map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((idx, outcome)) => outcomes[idx] = Some(outcome),
+            Err(e) => error!("Vote submission task failed: {e}"),
+        }
+    }
+
+    // A panicked task leaves no outcome; report it as a failure for that signer
+    outcomes
+        .into_iter()
+        .zip(signers)
+        .map(|(outcome, signer)| {
+            outcome.unwrap_or_else(|| SignerOutcome {
+                signer: signer.address(),
+                name: signer_name(signer.as_ref()),
+                attempts: 0,
+                elapsed: Duration::ZERO,
+                result: Err(anyhow::anyhow!("submission task panicked")),
+            })
+        })
+        .collect()
+}
+
+/// Log one line per signer plus totals
+pub fn log_outcomes(outcomes: &[SignerOutcome]) {
+    for outcome in outcomes {
+        match &outcome.result {
+            Ok(response) => info!(
+                "Validator vote success for signer {} after {} attempt(s) in {}ms: {response:?}",
+                outcome.name,
+                outcome.attempts,
+                outcome.elapsed.as_millis()
+            ),
+            Err(e) => error!(
+                "Failed to submit vote for signer {} after {} attempt(s) in {}ms ({}): {e}",
+                outcome.name,
+                outcome.attempts,
+                outcome.elapsed.as_millis(),
+                disposition(e)
+            ),
+        }
+    }
+    let succeeded = outcomes.iter().filter(|o| o.is_success()).count();
+    info!(
+        "Vote submission complete: {succeeded} succeeded, {} failed",
+        outcomes.len() - succeeded
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::Network;
+    use crate::mock_http::{json, response, serve};
+    use alloy::signers::local::PrivateKeySigner;
+    use std::sync::atomic::Ordering;
+
+    fn ok() -> String {
+        json(r#"{"status":"ok","response":{"type":"default"}}"#)
+    }
+
+    fn unavailable() -> String {
+        response("503 Service Unavailable", "", "")
+    }
+
+    fn rejected() -> String {
+        json(r#"{"status":"err","response":"denied"}"#)
+    }
+
+    fn rate_limited() -> String {
+        json(r#"{"status":"err","response":"Too many cumulative requests sent"}"#)
+    }
+
+    fn client(url: &str) -> HyperliquidClient {
+        HyperliquidClient::new(Network::custom(url, "b", 1337).unwrap()).with_max_clock_skew(None)
+    }
+
+    fn wallet() -> PrivateKeySigner {
+        PrivateKeySigner::random()
+    }
+
+    fn shared(wallet: PrivateKeySigner) -> Arc<dyn VoteSigner> {
+        Arc::new(wallet)
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(20),
+            jitter: 0.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_transient_failures() {
+        let (url, count) = serve(vec![unavailable(), rate_limited(), ok()]);
+        let outcome = submit_with_retry(&client(&url), &wallet(), "0.035", &fast_policy()).await;
+        assert_eq!(outcome.result.unwrap().type_string, "default");
+        assert_eq!(outcome.attempts, 3);
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_rejections() {
+        let (url, count) = serve(vec![rejected(), ok()]);
+        let outcome = submit_with_retry(&client(&url), &wallet(), "0.035", &fast_policy()).await;
+        let error = outcome.result.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ExchangeError>(),
+            Some(ExchangeError::Rejected(message)) if message == "denied"
+        ));
+        assert_eq!(outcome.attempts, 1);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn submits_all_signers_in_order() {
+        let (url, count) = serve(vec![ok(), ok(), ok()]);
+        let signers = vec![shared(wallet()), shared(wallet()), shared(wallet())];
+        let outcomes = submit_all(Arc::new(client(&url)), &signers, "0.035", &fast_policy()).await;
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+        let addresses: Vec<Address> = signers.iter().map(|s| s.address()).collect();
+        assert_eq!(
+            outcomes.iter().map(|o| o.signer).collect::<Vec<_>>(),
+            addresses
+        );
+        assert!(outcomes.iter().all(SignerOutcome::is_success));
+    }
+}
@@ -1,8 +1,26 @@
-use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+use alloy::primitives::Address;
+use alloy_signer::Signature;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+/// `type` tag of the vote action
+pub const VALIDATOR_L1_STREAM: &str = "validatorL1Stream";
+
+/// HyperCore L1 action, signed via the phantom agent EIP-712 scheme
+/// Dev: the action hash is msgpack of the fields in declaration order, so implementors must
+///      declare `type` first and the remaining fields in the order the exchange expects
+pub trait L1Action: Serialize + DeserializeOwned + Send + Sync {
+    /// Expected `type` tag of the action
+    const TYPE: &'static str;
+
+    /// `type` tag carried by this action
+    fn type_tag(&self) -> &str;
+}
 
 /// `validatorL1Stream` vote action
 /// Ref: https://hyperliquid.gitbook.io/hyperliquid-docs/for-developers/api/exchange-endpoint?q=validatorL1Stream#validator-vote-on-risk-free-rate-for-aligned-quote-asset
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ValidatorL1StreamAction {
     /// Forced rename because `type` is a reserved keyword in Rust
@@ -11,37 +29,95 @@ pub struct ValidatorL1StreamAction {
     pub risk_free_rate: String,
 }
 
+impl L1Action for ValidatorL1StreamAction {
+    const TYPE: &'static str = VALIDATOR_L1_STREAM;
+
+    fn type_tag(&self) -> &str {
+        &self.type_string
+    }
+}
+
 impl ValidatorL1StreamAction {
     pub fn new(rate: &str) -> Self {
         Self {
-            type_string: "validatorL1Stream".to_string(),
+            type_string: VALIDATOR_L1_STREAM.to_string(),
             risk_free_rate: rate.to_string(),
         }
     }
 }
 
-/// `/exchange` request payload
-#[derive(Debug, Serialize)]
+/// Optional L1 action parameters covered by the action hash
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ActionOptions {
+    /// Vault (or subaccount) the action is performed on behalf of
+    pub vault_address: Option<Address>,
+    /// Millisecond timestamp after which the exchange rejects the action
+    pub expires_after: Option<u64>,
+}
+
+/// `/exchange` request payload for an action `A` (the rate vote by default)
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct ExchangeRequest<'a> {
-    pub action: ValidatorL1StreamAction,
+pub struct ExchangeRequest<'a, A = ValidatorL1StreamAction> {
+    pub action: A,
     pub nonce: u64,
+    #[serde(borrow)]
     pub signature: SignatureReq<'a>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vault_address: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_after: Option<u64>,
+}
+
+impl<A> ExchangeRequest<'_, A> {
+    /// Hashing options carried by this request
+    pub fn options(&self) -> ActionOptions {
+        ActionOptions {
+            vault_address: self.vault_address,
+            expires_after: self.expires_after,
+        }
+    }
 }
 
 /// Encoded wallet signature (r, s, v)
-#[derive(Debug, Serialize)]
+/// Dev: `r` and `s` borrow from the input when deserialized, and are owned when encoded
+///      from a `Signature`
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignatureReq<'a> {
-    pub r: &'a str,
-    pub s: &'a str,
+    #[serde(borrow)]
+    pub r: Cow<'a, str>,
+    #[serde(borrow)]
+    pub s: Cow<'a, str>,
     pub v: u64,
 }
 
+impl SignatureReq<'static> {
+    /// Encode a signature in the wire format (hex `r`, `s` and `v` of 27 or 28)
+    pub fn from_signature(signature: &Signature) -> Self {
+        Self {
+            r: format!("0x{:x}", signature.r()).into(),
+            s: format!("0x{:x}", signature.s()).into(),
+            v: 27 + signature.v() as u64,
+        }
+    }
+}
+
 /// `/exchange` request response
 #[derive(Debug, Deserialize)]
 #[serde(tag = "status", rename_all = "lowercase")]
 pub enum ExchangeResponse {
     // Handles `ok`, `err` `status` fields dynamically
-    Ok { response: serde_json::Value },
+    Ok { response: ActionResponse },
     Err { response: String },
 }
+
+/// Successful action response, e.g. `{"type": "default"}` for `validatorL1Stream`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ActionResponse {
+    /// Forced rename because `type` is a reserved keyword in Rust
+    #[serde(rename = "type")]
+    pub type_string: String,
+    /// Action-specific payload; absent for `validatorL1Stream`
+    #[serde(default)]
+    pub data: Option<serde_json::Value>,
+}
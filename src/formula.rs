@@ -0,0 +1,303 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+use std::{env, fs};
+
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::sources::de::de_date;
+
+/// Environment variable pointing to a JSON file of rate formulas
+pub const FORMULA_FILE_ENV: &str = "AQA_FORMULA_FILE";
+
+/// Integer ratio applied with integer arithmetic to avoid floating point rounding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct Ratio {
+    pub numerator: u64,
+    pub denominator: u64,
+}
+
+impl fmt::Display for Ratio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+/// Where flooring happens when applying the basis conversion and scalar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingOrder {
+    /// Floor after the basis conversion, then again after the scalar (v1 behaviour)
+    #[default]
+    EachStep,
+    /// Apply both ratios at once and floor a single time
+    Final,
+}
+
+/// Versioned definition of how the median SOFR average becomes the AQA rate
+///
+/// AQA rate = floor(floor(sofr_avg × basis) × scalar) for `RoundingOrder::EachStep`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RateFormula {
+    /// Governance version identifier (e.g., `v1`)
+    pub version: String,
+    /// First query date this formula applies to
+    #[serde(deserialize_with = "de_date")]
+    pub effective_from: NaiveDate,
+    /// Day-count basis conversion (ACT/360 -> ACT/365.25 is 487/480)
+    pub basis: Ratio,
+    /// Scalar approximating offchain reserve income relative to SOFR
+    pub scalar: Ratio,
+    /// Rounding order of the two ratios
+    #[serde(default)]
+    pub rounding: RoundingOrder,
+}
+
+impl Default for RateFormula {
+    /// Formula in effect since the first AQA release: ACT/365.25 basis, 85% scalar
+    fn default() -> Self {
+        Self {
+            version: "v1".to_string(),
+            // Covers the full SOFR history (first published 2018-04-03)
+            effective_from: NaiveDate::from_ymd_opt(2018, 4, 2).unwrap(),
+            basis: Ratio {
+                numerator: 487,
+                denominator: 480,
+            },
+            scalar: Ratio {
+                numerator: 85,
+                denominator: 100,
+            },
+            rounding: RoundingOrder::EachStep,
+        }
+    }
+}
+
+impl fmt::Display for RateFormula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (effective {}, basis {}, scalar {}, rounding {:?})",
+            self.version, self.effective_from, self.basis, self.scalar, self.rounding
+        )
+    }
+}
+
+impl RateFormula {
+    /// Apply the formula to a scaled SOFR average (1% = 1,000,000), flooring per `rounding`
+    pub fn apply(&self, sofr_avg: u64) -> Result<u64> {
+        let (basis, scalar) = (self.basis, self.scalar);
+        let value = sofr_avg as u128;
+        let rate = match self.rounding {
+            RoundingOrder::EachStep => value
+                .checked_mul(basis.numerator as u128)
+                .map(|v| v / basis.denominator as u128)
+                .and_then(|v| v.checked_mul(scalar.numerator as u128))
+                .map(|v| v / scalar.denominator as u128),
+            RoundingOrder::Final => value
+                .checked_mul(basis.numerator as u128)
+                .and_then(|v| v.checked_mul(scalar.numerator as u128))
+                .map(|v| v / (basis.denominator as u128 * scalar.denominator as u128)),
+        };
+        rate.and_then(|r| u64::try_from(r).ok())
+            .ok_or_else(|| anyhow!("AQA rate overflow applying formula {}", self.version))
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.version.trim().is_empty() {
+            bail!("formula version must not be empty");
+        }
+        if self.basis.denominator == 0 || self.scalar.denominator == 0 {
+            bail!("formula {} has a zero denominator", self.version);
+        }
+        Ok(())
+    }
+}
+
+/// Set of rate formulas, selected by query date
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormulaSet {
+    /// Sorted by ascending `effective_from`
+    formulas: Vec<RateFormula>,
+}
+
+impl Default for FormulaSet {
+    fn default() -> Self {
+        Self {
+            formulas: vec![RateFormula::default()],
+        }
+    }
+}
+
+impl FormulaSet {
+    /// Build a validated set: non-empty, unique versions and unique effective dates
+    pub fn new(mut formulas: Vec<RateFormula>) -> Result<Self> {
+        if formulas.is_empty() {
+            bail!("at least one rate formula is required");
+        }
+        let mut versions = HashSet::new();
+        let mut dates = HashSet::new();
+        for formula in &formulas {
+            formula.validate()?;
+            if !versions.insert(formula.version.as_str()) {
+                bail!("duplicate rate formula version {}", formula.version);
+            }
+            if !dates.insert(formula.effective_from) {
+                bail!(
+                    "multiple rate formulas effective from {}",
+                    formula.effective_from
+                );
+            }
+        }
+        formulas.sort_by_key(|f| f.effective_from);
+        Ok(Self { formulas })
+    }
+
+    /// Load formulas from a JSON array file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read rate formulas from {}", path.display()))?;
+        let formulas: Vec<RateFormula> = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse rate formulas in {}", path.display()))?;
+        Self::new(formulas)
+    }
+
+    /// Load formulas from `AQA_FORMULA_FILE` if set, otherwise use the built-in formula
+    pub fn from_env() -> Result<Self> {
+        match env::var(FORMULA_FILE_ENV) {
+            Ok(path) => Self::from_file(Path::new(&path)),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Formula in effect on `date`: the latest one with `effective_from <= date`
+    pub fn for_date(&self, date: NaiveDate) -> Result<&RateFormula> {
+        self.formulas
+            .iter()
+            .rev()
+            .find(|f| f.effective_from <= date)
+            .ok_or_else(|| anyhow!("no rate formula in effect on {date}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::adjust_basis;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn formula(version: &str, effective_from: NaiveDate, scalar: u64) -> RateFormula {
+        RateFormula {
+            version: version.to_string(),
+            effective_from,
+            scalar: Ratio {
+                numerator: scalar,
+                denominator: 100,
+            },
+            ..RateFormula::default()
+        }
+    }
+
+    mod apply_tests {
+        use super::*;
+
+        #[test]
+        fn default_matches_v1_constants() {
+            for rate in [0, 1, 3_464_999, 4_293_200, 5_000_000, 15_000_000] {
+                assert_eq!(
+                    RateFormula::default().apply(rate).unwrap(),
+                    adjust_basis(rate) * 85 / 100
+                );
+            }
+        }
+
+        #[test]
+        fn rounding_order() {
+            let each_step = RateFormula::default();
+            let final_only = RateFormula {
+                rounding: RoundingOrder::Final,
+                ..RateFormula::default()
+            };
+
+            // 5_000_000 * 487 / 480 = 5_072_916.67 -> 5_072_916 -> 4_311_978.6 -> 4_311_978
+            assert_eq!(each_step.apply(5_000_000).unwrap(), 4_311_978);
+            // 5_000_000 * 487 * 85 / 48_000 = 4_311_979.17 -> 4_311_979
+            assert_eq!(final_only.apply(5_000_000).unwrap(), 4_311_979);
+        }
+
+        #[test]
+        fn overflow_is_an_error() {
+            let formula = RateFormula {
+                scalar: Ratio {
+                    numerator: u64::MAX,
+                    denominator: 1,
+                },
+                ..RateFormula::default()
+            };
+            assert!(formula.apply(u64::MAX).is_err());
+        }
+    }
+
+    mod formula_set_tests {
+        use super::*;
+
+        #[test]
+        fn selects_by_date() {
+            let set = FormulaSet::new(vec![
+                formula("v2", date(2026, 1, 1), 80),
+                formula("v1", date(2025, 1, 1), 85),
+            ])
+            .unwrap();
+
+            assert!(set.for_date(date(2024, 12, 31)).is_err());
+            assert_eq!(set.for_date(date(2025, 6, 1)).unwrap().version, "v1");
+            assert_eq!(set.for_date(date(2025, 12, 31)).unwrap().version, "v1");
+            assert_eq!(set.for_date(date(2026, 1, 1)).unwrap().version, "v2");
+        }
+
+        #[test]
+        fn rejects_invalid_sets() {
+            assert!(FormulaSet::new(vec![]).is_err());
+            assert!(
+                FormulaSet::new(vec![
+                    formula("v1", date(2025, 1, 1), 85),
+                    formula("v1", date(2026, 1, 1), 80),
+                ])
+                .is_err()
+            );
+            assert!(
+                FormulaSet::new(vec![
+                    formula("v1", date(2025, 1, 1), 85),
+                    formula("v2", date(2025, 1, 1), 80),
+                ])
+                .is_err()
+            );
+            let mut zero = formula("v1", date(2025, 1, 1), 85);
+            zero.basis.denominator = 0;
+            assert!(FormulaSet::new(vec![zero]).is_err());
+        }
+
+        #[test]
+        fn parses_json() {
+            let json = r#"[
+                {
+                    "version": "v2",
+                    "effective_from": "2026-01-01",
+                    "basis": { "numerator": 487, "denominator": 480 },
+                    "scalar": { "numerator": 80, "denominator": 100 },
+                    "rounding": "final"
+                }
+            ]"#;
+            let formulas: Vec<RateFormula> = serde_json::from_str(json).unwrap();
+            let set = FormulaSet::new(formulas).unwrap();
+            let v2 = set.for_date(date(2026, 2, 1)).unwrap();
+            assert_eq!(v2.scalar.numerator, 80);
+            assert_eq!(v2.rounding, RoundingOrder::Final);
+        }
+    }
+}
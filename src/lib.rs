@@ -1,18 +1,26 @@
+pub mod accrual;
 pub mod chain;
+pub mod formula;
 pub mod sources;
 pub mod utils;
 
+#[cfg(test)]
+mod mock_http;
+
 use anyhow::{Result, bail};
 use chrono::NaiveDate;
-use log::{debug, error};
-use sources::{Source, fred::Fred, nyfed::NYFed, ofr::OFR};
-
-use crate::utils::adjust_basis;
+use formula::FormulaSet;
+use log::{debug, error, info};
+use sources::{Source, fred::Fred, health, nyfed::NYFed, ofr::OFR};
+use std::time::Instant;
 
 /// Query all three SOFR data sources and return the median value.
 ///
 /// This function queries FRED, NY Fed, and OFR sources for the 30-day SOFR average.
 /// It prints the result from each source (or an error message if a source fails).
+/// Each outcome is recorded in the process-wide `sources::health` registry. Once the
+/// circuit breaker is enabled (by the daemon), sources whose breaker is open are skipped
+/// without being fetched, as long as at least two sources are still fetched.
 ///
 /// # Returns
 /// Returns the median (date, value) tuple if validation passes. The value is in scaled
@@ -30,38 +38,53 @@ use crate::utils::adjust_basis;
 ///
 /// let date = Local::now().date_naive();
 /// let (median_date, median_value) = get_median_sofr_avg(date)?;
-/// println!("Median: {} on {}", median_value, median_date);
+/// println!("Median: {median_value} on {median_date}");
 /// # Ok::<(), anyhow::Error>(())
 /// ```
 pub fn get_median_sofr_avg(date: NaiveDate) -> Result<(NaiveDate, u64)> {
     // Setup all three data sources
-    let fred = Fred::default();
-    let nyfed = NYFed::default();
-    let ofr = OFR::default();
+    let fred = Fred;
+    let nyfed = NYFed;
+    let ofr = OFR;
 
     // Track returned results from each data source
-    let mut results: Vec<(&str, NaiveDate, u64)> = Vec::new();
-
-    let mut collect_from = |source: &dyn Source| match source.collect(date) {
-        Ok((source_date, source_value)) => {
-            debug!(
-                "{} 30-day SOFR avg on {}: {}",
-                source.name(),
-                source_date,
-                source_value
-            );
-            results.push((source.name(), source_date, source_value));
-        }
-        Err(e) => {
-            error!("{} failed: {}", source.name(), e);
+    let mut results: Vec<(&'static str, NaiveDate, u64)> = Vec::new();
+
+    // Skip sources whose circuit breaker is open, keeping enough for a median and
+    // fetching a skipped source in place of each one that fails
+    let sources: [&dyn Source; 3] = [&fred, &nyfed, &ofr];
+    let names: Vec<&'static str> = sources.iter().map(|s| s.name()).collect();
+    let mut admission = health::registry().admit(&names, Instant::now());
+
+    let mut outcomes = Vec::new();
+    while let Some(name) = admission.next_source() {
+        let Some(source) = sources.iter().find(|s| s.name() == name) else {
+            continue;
+        };
+
+        let started = Instant::now();
+        match source.observe(date) {
+            Ok(observation) => {
+                outcomes.push((name, Ok(started.elapsed())));
+                debug!(
+                    "{name} 30-day SOFR avg on {}: {} (served by {})",
+                    observation.date, observation.value, observation.endpoint
+                );
+                results.push((name, observation.date, observation.value));
+            }
+            Err(e) => {
+                outcomes.push((name, Err(format!("{e:#}"))));
+                error!("{name} failed: {e}");
+                admission.replace_failed(name);
+            }
         }
-    };
-
-    collect_from(&fred);
-    collect_from(&nyfed);
-    collect_from(&ofr);
+    }
+    health::registry().record_run(outcomes, &admission.skipped(), Instant::now());
 
-    compute_validated_median(date, results)
+    let values: Vec<(&'static str, u64)> = results.iter().map(|(n, _, v)| (*n, *v)).collect();
+    let median = compute_validated_median(date, results)?;
+    health::registry().record_consensus(&values, median.1);
+    Ok(median)
 }
 
 /// Compute the validated median from a set of source results.
@@ -110,7 +133,7 @@ pub fn compute_validated_median(
             "All pairs of sources differ by more than 5 bps. Values: {}",
             results
                 .iter()
-                .map(|(name, _, val)| format!("{}: {}", name, val))
+                .map(|(name, _, val)| format!("{name}: {val}"))
                 .collect::<Vec<_>>()
                 .join(", ")
         );
@@ -125,20 +148,12 @@ pub fn compute_validated_median(
     for (name, _, val) in &results {
         // Check upper bound
         if *val > MAX_RATE {
-            bail!(
-                "Rate from {} ({}) exceeds maximum plausible value of 15%",
-                name,
-                val
-            );
+            bail!("Rate from {name} ({val}) exceeds maximum plausible value of 15%");
         }
         // Check lower bound (treating u64 values > i64::MAX as negative via two's complement)
         let val_signed = *val as i64;
         if val_signed < MIN_RATE {
-            bail!(
-                "Rate from {} ({}) below minimum plausible value of -5%",
-                name,
-                val
-            );
+            bail!("Rate from {name} ({val}) below minimum plausible value of -5%");
         }
     }
 
@@ -163,11 +178,7 @@ pub fn compute_validated_median(
 
     if days_behind > MAX_STALENESS_DAYS {
         bail!(
-            "Data is too stale: median source date {} is {} days behind query date {} (max {} days allowed)",
-            median_returned_date,
-            days_behind,
-            query_date,
-            MAX_STALENESS_DAYS
+            "Data is too stale: median source date {median_returned_date} is {days_behind} days behind query date {query_date} (max {MAX_STALENESS_DAYS} days allowed)"
         );
     }
 
@@ -190,29 +201,39 @@ pub fn compute_validated_median(
     Ok((median_date, median_value))
 }
 
-/// Scalar applied to the median SOFR average which best approximates a deployer's
-/// offchain reserve income. This scalar is derived from market research and is an
-/// estimate of the ratio of offchain reserve income to SOFR in competitive conditions.
-///
-/// Represented as a ratio (numerator, denominator) to avoid floating point arithmetic.
-/// AQA rate = 85% of SOFR rate
-const AQA_SCALAR_NUMERATOR: u64 = 85;
-const AQA_SCALAR_DENOMINATOR: u64 = 100;
-
 /// Get both the raw 30-day SOFR average and the AQA reference rate.
 ///
+/// The rate formula (basis conversion, scalar, rounding order) in effect on `date` is
+/// selected from `formulas` and logged, so historical dates are recomputed with the
+/// formula that applied at the time.
+///
 /// # Returns
 /// Returns a tuple of (date, median_value, reference_rate) where:
 /// - `date` is the median date from the sources
 /// - `median_value` is the scaled 30-day SOFR average
-/// - `reference_rate` is the scaled rate (basis_adjusted_raw_sofr_avg * 0.85)
+/// - `reference_rate` is the scaled rate (basis_adjusted_raw_sofr_avg * scalar)
 ///
 /// All values are in scaled units where 1% = 1,000,000.
-pub fn get_aqa_ref_rate(date: NaiveDate) -> Result<(NaiveDate, u64, u64)> {
+///
+/// # Rounding
+/// Source values are 5 decimal place averages:
+/// 1. FRED and NY Fed publish the NY Fed's own average; OFR's is compounded locally and rounded
+///    half-up to 5 decimal places, as the NY Fed rounds it (`Rounding::HalfUp(5)`)
+/// 2. Each value is then scaled to integer units, flooring anything past 6 decimal places
+///    (a no-op for 5 decimal place values)
+///
+/// From there the path to the submitted vote is integer-only and every step floors
+/// (payor-friendly):
+/// 1. An even-count median floors the mean of the two middle values
+/// 2. The formula floors after the basis conversion and after the scalar
+///    (or once, for `RoundingOrder::Final`)
+///
+/// `fmt_scaled_rate` then prints the result exactly, without further rounding.
+pub fn get_aqa_ref_rate(date: NaiveDate, formulas: &FormulaSet) -> Result<(NaiveDate, u64, u64)> {
+    let formula = formulas.for_date(date)?;
+    info!("Using AQA rate formula {formula}");
+
     let (median_date, median_value) = get_median_sofr_avg(date)?;
-    // Adjust SOFR basis
-    let basis_adjusted_rate = adjust_basis(median_value);
-    // Use integer arithmetic to avoid floating point rounding issues
-    let reference_rate = (basis_adjusted_rate * AQA_SCALAR_NUMERATOR) / AQA_SCALAR_DENOMINATOR;
+    let reference_rate = formula.apply(median_value)?;
     Ok((median_date, median_value, reference_rate))
 }
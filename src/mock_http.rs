@@ -0,0 +1,55 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Raw HTTP response with `status` (e.g. `503 Service Unavailable`), extra `headers`
+/// (each ending in `\r\n`) and `body`
+pub fn response(status: &str, headers: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {status}\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// `200 OK` JSON response with `body`
+pub fn json(body: &str) -> String {
+    response("200 OK", "Content-Type: application/json\r\n", body)
+}
+
+/// Serve raw `responses` in order on a local port, one per connection, after reading each
+/// request in full; returns the base URL (without trailing slash) and a request counter
+pub fn serve(responses: Vec<String>) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let count = Arc::new(AtomicUsize::new(0));
+    let served = count.clone();
+    thread::spawn(move || {
+        for response in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            read_request(&mut reader);
+            served.fetch_add(1, Ordering::SeqCst);
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+        }
+    });
+    (format!("http://{addr}"), count)
+}
+
+/// Consume one request's headers and body
+fn read_request<R: BufRead>(reader: &mut R) {
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap() == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().unwrap();
+        }
+    }
+    reader.read_exact(&mut vec![0; content_length]).unwrap();
+}
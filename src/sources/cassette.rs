@@ -0,0 +1,252 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{Context, Result, anyhow, bail};
+use log::debug;
+
+/// How source fetches interact with recorded cassettes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CassetteMode {
+    /// Fetch live; cassettes are ignored
+    #[default]
+    Off,
+    /// Fetch live and save every successful response
+    Record,
+    /// Serve recorded responses only; any unrecorded request fails
+    Replay,
+}
+
+impl FromStr for CassetteMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" | "" => Ok(Self::Off),
+            "record" => Ok(Self::Record),
+            "replay" => Ok(Self::Replay),
+            other => bail!("unknown cassette mode '{other}' (expected off, record or replay)"),
+        }
+    }
+}
+
+/// Directory of recorded responses, one file per URL
+///
+/// Each file is named after a stable hash of the URL and holds the URL on its first line
+/// followed by the raw response body, so recordings can be inspected and diffed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cassette {
+    pub mode: CassetteMode,
+    pub dir: PathBuf,
+}
+
+impl Cassette {
+    pub fn new(mode: CassetteMode, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            mode,
+            dir: dir.into(),
+        }
+    }
+
+    /// Build from `SOURCE_CASSETTE_MODE` (default `off`) and `SOURCE_CASSETTE_DIR`
+    /// (default `cassettes`)
+    pub fn from_env() -> Result<Self> {
+        let mode = match std::env::var("SOURCE_CASSETTE_MODE") {
+            Ok(v) => v.parse().context("Invalid SOURCE_CASSETTE_MODE")?,
+            Err(_) => CassetteMode::Off,
+        };
+        let dir = std::env::var("SOURCE_CASSETTE_DIR").unwrap_or_else(|_| "cassettes".into());
+        Ok(Self::new(mode, dir))
+    }
+
+    /// Path of the cassette recorded for `url`
+    pub fn path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{}.cassette", key(url)))
+    }
+
+    /// Serve `url` according to `mode`, calling `live` for real fetches
+    pub fn fetch(&self, url: &str, live: impl FnOnce() -> Result<Vec<u8>>) -> Result<Vec<u8>> {
+        match self.mode {
+            CassetteMode::Off => live(),
+            CassetteMode::Replay => self.load(url),
+            CassetteMode::Record => {
+                let body = live()?;
+                self.save(url, &body)?;
+                Ok(body)
+            }
+        }
+    }
+
+    fn load(&self, url: &str) -> Result<Vec<u8>> {
+        let path = self.path(url);
+        let contents = fs::read(&path).map_err(|e| {
+            anyhow!(
+                "no cassette recorded for {url} ({}: {e}); record with SOURCE_CASSETTE_MODE=record",
+                path.display()
+            )
+        })?;
+        let split = contents
+            .iter()
+            .position(|b| *b == b'\n')
+            .ok_or_else(|| anyhow!("malformed cassette {}", path.display()))?;
+        let recorded_url = String::from_utf8_lossy(&contents[..split]);
+        if recorded_url != url {
+            bail!(
+                "cassette {} was recorded for {recorded_url}, not {url}",
+                path.display()
+            );
+        }
+        debug!("Replayed {url} from {}", path.display());
+        Ok(contents[split + 1..].to_vec())
+    }
+
+    fn save(&self, url: &str, body: &[u8]) -> Result<()> {
+        if url.contains('\n') {
+            bail!("cannot record URL containing a newline: {url:?}");
+        }
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("creating cassette directory {}", self.dir.display()))?;
+
+        // Write then rename, so concurrent recordings of the same URL never interleave
+        static WRITES: AtomicU64 = AtomicU64::new(0);
+        let path = self.path(url);
+        let tmp = path.with_extension(format!(
+            "tmp.{}.{}",
+            std::process::id(),
+            WRITES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut contents = Vec::with_capacity(url.len() + 1 + body.len());
+        contents.extend_from_slice(url.as_bytes());
+        contents.push(b'\n');
+        contents.extend_from_slice(body);
+        fs::write(&tmp, contents).with_context(|| format!("writing {}", tmp.display()))?;
+        fs::rename(&tmp, &path).with_context(|| format!("writing {}", path.display()))?;
+        debug!("Recorded {url} to {}", path.display());
+        Ok(())
+    }
+}
+
+/// Stable cassette key for `url` (64-bit FNV-1a, hex)
+/// Dev: std hashers are not guaranteed stable across releases, so checked-in names would drift
+pub fn key(url: &str) -> String {
+    let hash = url.bytes().fold(0xcbf29ce484222325u64, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{hash:016x}")
+}
+
+/// Process-wide cassette used by `get_bytes`
+static CASSETTE: OnceLock<Cassette> = OnceLock::new();
+
+/// Install the process-wide cassette; fails if one was already installed or used
+pub fn init(cassette: Cassette) -> Result<()> {
+    CASSETTE
+        .set(cassette)
+        .map_err(|_| anyhow!("source cassette already initialized"))
+}
+
+/// Process-wide cassette, configured from the environment on first use
+pub fn cassette() -> Result<&'static Cassette> {
+    if let Some(cassette) = CASSETTE.get() {
+        return Ok(cassette);
+    }
+    let cassette = Cassette::from_env()?;
+    Ok(CASSETTE.get_or_init(|| cassette))
+}
+
+/// Whether `dir` holds at least one recorded cassette
+pub fn has_recordings(dir: &Path) -> bool {
+    fs::read_dir(dir).is_ok_and(|entries| {
+        entries
+            .flatten()
+            .any(|e| e.path().extension().is_some_and(|ext| ext == "cassette"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aqa-cassette-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn records_then_replays() {
+        let dir = temp_dir("roundtrip");
+        let url = "https://example.com/data.csv?start=2025-01-01&end=2025-01-15";
+
+        let recorder = Cassette::new(CassetteMode::Record, &dir);
+        let body = recorder
+            .fetch(url, || Ok(b"DATE,RATE\n2025-01-15,4.30\n".to_vec()))
+            .unwrap();
+        assert!(has_recordings(&dir));
+
+        let player = Cassette::new(CassetteMode::Replay, &dir);
+        let replayed = player
+            .fetch(url, || panic!("replay must not fetch live"))
+            .unwrap();
+        assert_eq!(replayed, body);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn replay_fails_on_unrecorded_request() {
+        let dir = temp_dir("missing");
+        let player = Cassette::new(CassetteMode::Replay, &dir);
+        let err = player
+            .fetch("https://example.com/missing", || Ok(vec![]))
+            .unwrap_err();
+        assert!(err.to_string().contains("no cassette recorded"));
+    }
+
+    #[test]
+    fn replay_rejects_mismatched_url() {
+        let dir = temp_dir("mismatch");
+        let player = Cassette::new(CassetteMode::Replay, &dir);
+        let url = "https://example.com/a";
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(player.path(url), b"https://example.com/b\nbody").unwrap();
+        let err = player.fetch(url, || Ok(vec![])).unwrap_err();
+        assert!(err.to_string().contains("was recorded for"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn record_does_not_save_failures() {
+        let dir = temp_dir("failure");
+        let recorder = Cassette::new(CassetteMode::Record, &dir);
+        assert!(
+            recorder
+                .fetch("https://example.com/", || bail!("503"))
+                .is_err()
+        );
+        assert!(!has_recordings(&dir));
+    }
+
+    #[test]
+    fn keys_are_stable() {
+        // FNV-1a test vectors
+        assert_eq!(key(""), "cbf29ce484222325");
+        assert_eq!(key("a"), "af63dc4c8601ec8c");
+        assert_ne!(key("https://a/?x=1"), key("https://a/?x=2"));
+    }
+
+    #[test]
+    fn parses_modes() {
+        assert_eq!(
+            "Replay".parse::<CassetteMode>().unwrap(),
+            CassetteMode::Replay
+        );
+        assert_eq!(
+            "record".parse::<CassetteMode>().unwrap(),
+            CassetteMode::Record
+        );
+        assert_eq!("off".parse::<CassetteMode>().unwrap(), CassetteMode::Off);
+        assert!("live".parse::<CassetteMode>().is_err());
+    }
+}
@@ -0,0 +1,508 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Result, anyhow, bail};
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// Day-count basis used both to accrue each overnight rate and to annualize the result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DayCount {
+    /// Actual/360 (SOFR, EFFR)
+    #[default]
+    Act360,
+    /// Actual/365 Fixed (SONIA)
+    Act365Fixed,
+    /// Actual/365.25 (AQA publishing basis)
+    Act36525,
+}
+
+impl DayCount {
+    /// Days-per-year denominator of the basis
+    pub fn denominator(self) -> Decimal {
+        match self {
+            DayCount::Act360 => Decimal::from(360),
+            DayCount::Act365Fixed => Decimal::from(365),
+            DayCount::Act36525 => Decimal::new(36525, 2),
+        }
+    }
+}
+
+/// Business-day calendar deciding which dates carry a fixing
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Calendar {
+    /// Business days are exactly the dates present in the rate series
+    /// (what a published overnight series implicitly encodes)
+    #[default]
+    Published,
+    /// Monday to Friday, excluding the listed holidays
+    Weekdays(BTreeSet<NaiveDate>),
+}
+
+impl Calendar {
+    /// Whether `date` is a business day under this calendar
+    pub fn is_business_day(&self, date: NaiveDate, rates: &BTreeMap<NaiveDate, Decimal>) -> bool {
+        match self {
+            Calendar::Published => rates.contains_key(&date),
+            Calendar::Weekdays(holidays) => {
+                !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !holidays.contains(&date)
+            }
+        }
+    }
+
+    /// Latest business day on or before `date`, bounded by the start of the rate series
+    fn business_day_on_or_before(
+        &self,
+        date: NaiveDate,
+        rates: &BTreeMap<NaiveDate, Decimal>,
+    ) -> Option<NaiveDate> {
+        let earliest = *rates.keys().next()?;
+        let mut day = date;
+        while day >= earliest {
+            if self.is_business_day(day, rates) {
+                return Some(day);
+            }
+            day = day.checked_sub_days(Days::new(1))?;
+        }
+        None
+    }
+
+    /// The `n`-th business day strictly before `date` (`date` itself when `n` is 0)
+    fn shift_back(
+        &self,
+        date: NaiveDate,
+        n: u32,
+        rates: &BTreeMap<NaiveDate, Decimal>,
+    ) -> Option<NaiveDate> {
+        let mut day = date;
+        for _ in 0..n {
+            day = self.business_day_on_or_before(day.checked_sub_days(Days::new(1))?, rates)?;
+        }
+        Some(day)
+    }
+}
+
+/// Optional ISDA backward-looking conventions, expressed in business days
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Convention {
+    /// Plain in-arrears compounding over the interest period
+    #[default]
+    None,
+    /// Rates and day weights both taken from a period shifted back `n` business days
+    ObservationShift(u32),
+    /// Rates taken `n` business days earlier, day weights from the interest period
+    Lookback(u32),
+    /// Rate of the business day `n` business days before period end is repeated to the end
+    Lockout(u32),
+}
+
+/// Rounding applied to the annualized percent result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rounding {
+    /// Keep full `Decimal` precision
+    #[default]
+    None,
+    /// Truncate to `n` decimal places of percent (payor-friendly)
+    Floor(u32),
+    /// Round half away from zero to `n` decimal places of percent
+    HalfUp(u32),
+}
+
+impl Rounding {
+    /// Apply rounding to a percent value
+    pub fn apply(self, value: Decimal) -> Decimal {
+        match self {
+            Rounding::None => value,
+            Rounding::Floor(dp) => value.round_dp_with_strategy(dp, RoundingStrategy::ToZero),
+            Rounding::HalfUp(dp) => {
+                value.round_dp_with_strategy(dp, RoundingStrategy::MidpointAwayFromZero)
+            }
+        }
+    }
+}
+
+/// Parameters of a backward-looking compounded average
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompoundingSpec {
+    /// Length of the interest period in calendar days
+    pub period_days: u64,
+    /// Accrual and annualization basis
+    pub day_count: DayCount,
+    /// Calendar used to identify business days
+    pub calendar: Calendar,
+    /// Backward-looking convention
+    pub convention: Convention,
+    /// Rounding of the annualized result
+    pub rounding: Rounding,
+}
+
+impl CompoundingSpec {
+    /// NY Fed 30-Day Average SOFR methodology, as published (rounded to 5 decimal places)
+    /// Ref: https://www.newyorkfed.org/markets/reference-rates/additional-information-about-reference-rates#sofr_ai_calculation_methodology
+    pub fn sofr_30d_average() -> Self {
+        Self {
+            period_days: 30,
+            day_count: DayCount::Act360,
+            calendar: Calendar::Published,
+            convention: Convention::None,
+            rounding: Rounding::HalfUp(5),
+        }
+    }
+}
+
+/// Compute a compounded average rate published on `effective_date`
+///
+/// The interest period covers the `period_days` calendar days ending the day before
+/// `effective_date` (for a 30-day average published on Oct 7: Sep 7 to Oct 6). Each
+/// calendar day accrues at the rate of the latest business day on or before it, so a
+/// Friday rate typically applies for ni = 3 days.
+///
+/// Formula: ((∏(1 + ri × ni/B)) - 1) × B/dc
+/// where:
+/// - ri = rate (as percent) of business-day group i, after applying the convention
+/// - ni = number of calendar days group i applies for
+/// - B = day-count denominator (360 for ACT/360)
+/// - dc = number of calendar days in the (observation) period
+///
+/// `rates` are percent values (e.g., 4.29 for 4.29%); the result is a percent rounded
+/// per `spec.rounding` (intermediate products are never rounded).
+pub fn compound(
+    effective_date: NaiveDate,
+    rates: &BTreeMap<NaiveDate, Decimal>,
+    spec: &CompoundingSpec,
+) -> Result<Decimal> {
+    if rates.is_empty() {
+        bail!("no observations available")
+    }
+    if spec.period_days == 0 {
+        bail!("compounding period must be at least one day")
+    }
+
+    let calendar = &spec.calendar;
+    let mut start_date = effective_date
+        .checked_sub_days(Days::new(spec.period_days))
+        .ok_or_else(|| anyhow!("date underflow"))?;
+    let mut end_date = effective_date
+        .checked_sub_days(Days::new(1))
+        .ok_or_else(|| anyhow!("date underflow"))?;
+
+    // Observation shift moves the whole period (and thus day weights) back
+    if let Convention::ObservationShift(n) = spec.convention {
+        start_date = calendar
+            .shift_back(start_date, n, rates)
+            .ok_or_else(|| anyhow!("insufficient history before {start_date}"))?;
+        end_date = calendar
+            .shift_back(effective_date, n, rates)
+            .and_then(|d| d.checked_sub_days(Days::new(1)))
+            .ok_or_else(|| anyhow!("insufficient history before {effective_date}"))?;
+        if end_date < start_date {
+            bail!("observation shift leaves an empty period")
+        }
+    }
+
+    // Group calendar days by the business day whose fixing applies: (business day, ni)
+    let first = calendar
+        .business_day_on_or_before(start_date, rates)
+        .ok_or_else(|| anyhow!("insufficient history before {start_date}"))?;
+    let mut groups: Vec<(NaiveDate, u64)> = vec![(first, 0)];
+    let mut day = start_date;
+    while day <= end_date {
+        if day != first && calendar.is_business_day(day, rates) {
+            groups.push((day, 0));
+        }
+        groups.last_mut().unwrap().1 += 1;
+        day = day
+            .checked_add_days(Days::new(1))
+            .ok_or_else(|| anyhow!("date overflow"))?;
+    }
+
+    // Resolve the fixing date used for each group
+    let fixing_dates: Vec<NaiveDate> = match spec.convention {
+        Convention::None | Convention::ObservationShift(_) => {
+            groups.iter().map(|(d, _)| *d).collect()
+        }
+        Convention::Lookback(n) => groups
+            .iter()
+            .map(|(d, _)| {
+                calendar
+                    .shift_back(*d, n, rates)
+                    .ok_or_else(|| anyhow!("insufficient history before {d}"))
+            })
+            .collect::<Result<_>>()?,
+        Convention::Lockout(n) => {
+            let n = n as usize;
+            if n >= groups.len() {
+                bail!("lockout of {n} business days exceeds the period")
+            }
+            let cutoff = groups.len() - 1 - n;
+            groups
+                .iter()
+                .enumerate()
+                .map(|(i, (d, _))| if i > cutoff { groups[cutoff].0 } else { *d })
+                .collect()
+        }
+    };
+
+    // Compound: factor *= (1 + ri × ni/B)
+    let basis = spec.day_count.denominator();
+    let d100 = Decimal::ONE_HUNDRED;
+    let mut factor = Decimal::ONE;
+    let mut total_days = 0u64;
+    for ((_, ni), fixing_date) in groups.iter().zip(fixing_dates) {
+        let rate = rates
+            .get(&fixing_date)
+            .ok_or_else(|| anyhow!("missing rate for business day {fixing_date}"))?;
+        factor *= Decimal::ONE + (*rate / d100) * Decimal::from(*ni) / basis;
+        total_days += ni;
+    }
+
+    // Annualize over the period, convert back to percent and round
+    let avg_pct = (factor - Decimal::ONE) * (basis / Decimal::from(total_days)) * d100;
+    Ok(spec.rounding.apply(avg_pct))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    /// Weekday series of `rate` over the 45 days ending at `end`
+    fn weekday_series(end: NaiveDate, rate: Decimal) -> BTreeMap<NaiveDate, Decimal> {
+        (0..45)
+            .map(|i| end.checked_sub_days(Days::new(i)).unwrap())
+            .filter(|d| !matches!(d.weekday(), Weekday::Sat | Weekday::Sun))
+            .map(|d| (d, rate))
+            .collect()
+    }
+
+    mod rounding_tests {
+        use super::*;
+
+        #[test]
+        fn none_keeps_precision() {
+            assert_eq!(
+                Rounding::None.apply(dec!(4.29321499999)),
+                dec!(4.29321499999)
+            );
+        }
+
+        #[test]
+        fn floor_truncates() {
+            assert_eq!(Rounding::Floor(5).apply(dec!(4.293219999)), dec!(4.29321));
+            assert_eq!(Rounding::Floor(6).apply(dec!(4.2932199999)), dec!(4.293219));
+            assert_eq!(Rounding::Floor(5).apply(dec!(-0.000019)), dec!(-0.00001));
+        }
+
+        #[test]
+        fn half_up_rounds_midpoint_away_from_zero() {
+            assert_eq!(Rounding::HalfUp(5).apply(dec!(4.293215)), dec!(4.29322));
+            assert_eq!(Rounding::HalfUp(5).apply(dec!(4.2932149999)), dec!(4.29321));
+            assert_eq!(Rounding::HalfUp(5).apply(dec!(4.293225)), dec!(4.29323));
+            assert_eq!(Rounding::HalfUp(5).apply(dec!(-0.000015)), dec!(-0.00002));
+        }
+    }
+
+    mod compound_tests {
+        use super::*;
+
+        #[test]
+        fn flat_rate_every_day() {
+            // With one fixing per calendar day, the annualized product only exceeds
+            // the flat rate by the compounding effect
+            let eff = date(2025, 10, 3);
+            let rates: BTreeMap<_, _> = (0..45)
+                .map(|i| (eff.checked_sub_days(Days::new(i)).unwrap(), dec!(4.00)))
+                .collect();
+
+            let result = compound(eff, &rates, &CompoundingSpec::sofr_30d_average()).unwrap();
+            assert!(result > dec!(4.00) && result < dec!(4.01), "got {result}");
+        }
+
+        #[test]
+        fn weekend_weighting_matches_hand_computation() {
+            // 2025-10-06 (Monday) publication: period Sep 6 (Sat) to Oct 5 (Sun)
+            // Sep 6-7 carry Friday Sep 5; every Friday applies for 3 days
+            let eff = date(2025, 10, 6);
+            let rates = weekday_series(eff, dec!(3.60));
+
+            let spec = CompoundingSpec {
+                rounding: Rounding::None,
+                ..CompoundingSpec::sofr_30d_average()
+            };
+            let result = compound(eff, &rates, &spec).unwrap();
+
+            // 2 stub days + 4 weeks of (4 × 1 day + 1 × 3 days)
+            let r = dec!(0.036);
+            let basis = dec!(360);
+            let mut factor = Decimal::ONE + r * dec!(2) / basis;
+            for _ in 0..4 {
+                for _ in 0..4 {
+                    factor *= Decimal::ONE + r / basis;
+                }
+                factor *= Decimal::ONE + r * dec!(3) / basis;
+            }
+            let expected = (factor - Decimal::ONE) * (basis / dec!(30)) * dec!(100);
+            assert_eq!(result, expected);
+
+            // Published methodology rounds the same value to 5 decimal places
+            let published = compound(eff, &rates, &CompoundingSpec::sofr_30d_average()).unwrap();
+            assert_eq!(published, expected.round_dp(5));
+            assert_eq!(published.scale(), 5);
+        }
+
+        #[test]
+        fn weekday_calendar_matches_published_calendar() {
+            let eff = date(2025, 10, 6);
+            let rates = weekday_series(eff, dec!(4.10));
+
+            let published = compound(eff, &rates, &CompoundingSpec::sofr_30d_average()).unwrap();
+            let weekdays = compound(
+                eff,
+                &rates,
+                &CompoundingSpec {
+                    calendar: Calendar::Weekdays(BTreeSet::new()),
+                    ..CompoundingSpec::sofr_30d_average()
+                },
+            )
+            .unwrap();
+            assert_eq!(published, weekdays);
+        }
+
+        #[test]
+        fn weekday_calendar_requires_holiday_fixings() {
+            // A weekday missing from the series must be declared a holiday
+            let eff = date(2025, 10, 6);
+            let mut rates = weekday_series(eff, dec!(4.10));
+            let holiday = date(2025, 9, 15);
+            rates.remove(&holiday);
+
+            let mut spec = CompoundingSpec {
+                calendar: Calendar::Weekdays(BTreeSet::new()),
+                ..CompoundingSpec::sofr_30d_average()
+            };
+            let err = compound(eff, &rates, &spec).unwrap_err();
+            assert!(err.to_string().contains("missing rate"));
+
+            spec.calendar = Calendar::Weekdays(BTreeSet::from([holiday]));
+            assert_eq!(
+                compound(eff, &rates, &spec).unwrap(),
+                compound(eff, &rates, &CompoundingSpec::sofr_30d_average()).unwrap()
+            );
+        }
+
+        #[test]
+        fn day_count_basis() {
+            // Flat rate is preserved (up to compounding) whatever the basis
+            let eff = date(2025, 10, 6);
+            let rates = weekday_series(eff, dec!(5.00));
+            for day_count in [DayCount::Act360, DayCount::Act365Fixed, DayCount::Act36525] {
+                let spec = CompoundingSpec {
+                    day_count,
+                    ..CompoundingSpec::sofr_30d_average()
+                };
+                let result = compound(eff, &rates, &spec).unwrap();
+                assert!(result > dec!(5.00) && result < dec!(5.02), "got {result}");
+            }
+        }
+
+        #[test]
+        fn lookback_uses_earlier_fixings() {
+            // Rate steps up on the last business day; a 1-day lookback never sees it
+            let eff = date(2025, 10, 7);
+            let mut rates = weekday_series(eff, dec!(4.00));
+            rates.insert(date(2025, 10, 6), dec!(9.00));
+
+            let spec = CompoundingSpec {
+                convention: Convention::Lookback(1),
+                ..CompoundingSpec::sofr_30d_average()
+            };
+            let plain = compound(eff, &rates, &CompoundingSpec::sofr_30d_average()).unwrap();
+            let lookback = compound(eff, &rates, &spec).unwrap();
+            assert!(plain > dec!(4.1));
+            assert!(lookback < dec!(4.01), "got {lookback}");
+        }
+
+        #[test]
+        fn lockout_repeats_cutoff_fixing() {
+            // Last two business days are locked out to the rate two days before the end
+            let eff = date(2025, 10, 7);
+            let mut rates = weekday_series(eff, dec!(4.00));
+            rates.insert(date(2025, 10, 3), dec!(9.00));
+            rates.insert(date(2025, 10, 6), dec!(9.00));
+
+            let spec = CompoundingSpec {
+                convention: Convention::Lockout(2),
+                ..CompoundingSpec::sofr_30d_average()
+            };
+            let lockout = compound(eff, &rates, &spec).unwrap();
+            let flat = compound(
+                eff,
+                &weekday_series(eff, dec!(4.00)),
+                &CompoundingSpec::sofr_30d_average(),
+            )
+            .unwrap();
+            assert_eq!(lockout, flat);
+
+            let too_long = CompoundingSpec {
+                convention: Convention::Lockout(40),
+                ..CompoundingSpec::sofr_30d_average()
+            };
+            assert!(compound(eff, &rates, &too_long).is_err());
+        }
+
+        #[test]
+        fn observation_shift_moves_weights() {
+            // Shifting by 0 business days is the plain average
+            let eff = date(2025, 10, 6);
+            let rates = weekday_series(eff, dec!(4.20));
+            let plain = compound(eff, &rates, &CompoundingSpec::sofr_30d_average()).unwrap();
+            let shift_zero = compound(
+                eff,
+                &rates,
+                &CompoundingSpec {
+                    convention: Convention::ObservationShift(0),
+                    ..CompoundingSpec::sofr_30d_average()
+                },
+            )
+            .unwrap();
+            assert_eq!(plain, shift_zero);
+
+            // Rate spike before the interest period is only seen with a shift
+            let mut rates = rates;
+            rates.insert(date(2025, 9, 8), dec!(9.00));
+            let spec = CompoundingSpec {
+                calendar: Calendar::Weekdays(BTreeSet::new()),
+                convention: Convention::ObservationShift(5),
+                ..CompoundingSpec::sofr_30d_average()
+            };
+            let shifted = compound(date(2025, 10, 13), &rates, &spec).unwrap();
+            assert!(shifted > dec!(4.3), "got {shifted}");
+        }
+
+        #[test]
+        fn insufficient_history() {
+            let rates = BTreeMap::from([(date(2025, 10, 3), dec!(4.00))]);
+            let err = compound(
+                date(2025, 10, 3),
+                &rates,
+                &CompoundingSpec::sofr_30d_average(),
+            )
+            .unwrap_err();
+            assert!(err.to_string().contains("insufficient history"));
+        }
+
+        #[test]
+        fn empty_series() {
+            let err = compound(
+                date(2025, 10, 3),
+                &BTreeMap::new(),
+                &CompoundingSpec::sofr_30d_average(),
+            )
+            .unwrap_err();
+            assert!(err.to_string().contains("no observations"));
+        }
+    }
+}
@@ -2,6 +2,7 @@ use anyhow::{Result, anyhow};
 use chrono::NaiveDate;
 use csv::{ReaderBuilder, Trim};
 use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
 
 /// Trait helper so generic CSV parser can extract (date, value) from collected CSVs
 pub trait CSVRow {
@@ -41,3 +42,26 @@ where
         .ok_or_else(|| anyhow!("no observation found in CSV"))?;
     Ok((last.date(), last.value()))
 }
+
+/// Generic full-series CSV parser; collect data --> map of date -> value
+/// Rows without valid values are skipped, malformed rows fast-fail
+pub fn parse_csv_series<R>(body: &[u8]) -> Result<BTreeMap<NaiveDate, u64>>
+where
+    R: DeserializeOwned + CSVRow,
+{
+    let mut reader = ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(false)
+        .trim(Trim::All)
+        .from_reader(body);
+
+    let mut series = BTreeMap::new();
+    for result in reader.deserialize::<R>() {
+        let row = result?;
+        if row.has_value() {
+            series.insert(row.date(), row.value());
+        }
+    }
+
+    Ok(series)
+}
@@ -0,0 +1,132 @@
+use anyhow::{Context, Result, anyhow, bail};
+use log::warn;
+use reqwest::Url;
+
+/// Response body together with the endpoint that served it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fetched {
+    pub body: Vec<u8>,
+    /// Base URL of the endpoint (e.g., `https://fred.stlouisfed.org`)
+    pub endpoint: String,
+}
+
+/// Ordered endpoints of a source: primary first, then mirrors
+///
+/// Read from `env_var` as comma-separated base URLs (e.g., an internal caching proxy
+/// followed by the public API), falling back to `default`. Mirrors must serve the same
+/// paths and query strings as the public API.
+pub fn from_env(env_var: &str, default: &str) -> Result<Vec<String>> {
+    match std::env::var(env_var) {
+        Ok(list) => parse(&list).with_context(|| format!("Invalid {env_var}")),
+        Err(_) => Ok(vec![default.to_string()]),
+    }
+}
+
+/// Parse a comma-separated list of endpoint base URLs, dropping trailing slashes
+pub fn parse(list: &str) -> Result<Vec<String>> {
+    let endpoints = list
+        .split(',')
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .map(|e| {
+            Url::parse(e).with_context(|| format!("invalid endpoint '{e}'"))?;
+            Ok(e.trim_end_matches('/').to_string())
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if endpoints.is_empty() {
+        bail!("no endpoints listed");
+    }
+    Ok(endpoints)
+}
+
+/// Fetch `path` (starting with `/`) from each endpoint in order until one succeeds
+/// Each endpoint gets the full retry policy of `get` before falling back to the next
+pub fn fetch_first(
+    endpoints: &[String],
+    path: &str,
+    mut get: impl FnMut(&str) -> Result<Vec<u8>>,
+) -> Result<Fetched> {
+    let mut errors = Vec::new();
+    for (i, endpoint) in endpoints.iter().enumerate() {
+        match get(&format!("{endpoint}{path}")) {
+            Ok(body) => {
+                return Ok(Fetched {
+                    body,
+                    endpoint: endpoint.clone(),
+                });
+            }
+            Err(e) => {
+                if i + 1 < endpoints.len() {
+                    warn!("Endpoint {endpoint} failed: {e:#}; falling back to next endpoint");
+                }
+                errors.push(format!("{endpoint}: {e:#}"));
+            }
+        }
+    }
+    Err(anyhow!(
+        "all {} endpoint(s) failed for {path}: {}",
+        endpoints.len(),
+        errors.join("; ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoints(list: &[&str]) -> Vec<String> {
+        list.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn first_successful_endpoint_serves() {
+        let list = endpoints(&[
+            "http://proxy.internal",
+            "https://a.example",
+            "https://b.example",
+        ]);
+        let mut tried = Vec::new();
+        let fetched = fetch_first(&list, "/data.csv?x=1", |url| {
+            tried.push(url.to_string());
+            if url.starts_with("https://a.example") {
+                Ok(b"ok".to_vec())
+            } else {
+                bail!("unavailable")
+            }
+        })
+        .unwrap();
+
+        assert_eq!(fetched.endpoint, "https://a.example");
+        assert_eq!(fetched.body, b"ok");
+        assert_eq!(
+            tried,
+            [
+                "http://proxy.internal/data.csv?x=1",
+                "https://a.example/data.csv?x=1"
+            ]
+        );
+    }
+
+    #[test]
+    fn all_failures_are_reported() {
+        let list = endpoints(&["https://a.example", "https://b.example"]);
+        let err = fetch_first(&list, "/x", |url| bail!("down: {url}")).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("all 2 endpoint(s) failed"));
+        assert!(message.contains("down: https://a.example/x"));
+        assert!(message.contains("down: https://b.example/x"));
+    }
+
+    #[test]
+    fn parses_list() {
+        assert_eq!(
+            parse(" http://proxy.internal:8080/fred/ , https://fred.stlouisfed.org ").unwrap(),
+            [
+                "http://proxy.internal:8080/fred",
+                "https://fred.stlouisfed.org"
+            ]
+        );
+        assert!(parse("not a url").is_err());
+        assert!(parse(" , ").is_err());
+    }
+}
@@ -1,9 +1,10 @@
-use super::csv::{CSVRow, parse_csv_for_latest};
+use super::csv::{CSVRow, parse_csv_for_latest, parse_csv_series};
 use super::de::{de_date, de_scaled_opt};
-use crate::sources::{DEFAULT_LOOKBACK_WINDOW, Source, get_bytes, window};
+use crate::sources::{DEFAULT_LOOKBACK_WINDOW, Fetched, Source, endpoints, get_from, window};
 use anyhow::{Result, anyhow};
 use chrono::NaiveDate;
 use serde::Deserialize;
+use std::collections::BTreeMap;
 
 /// CSV row format returned from `fredgraph.csv` endpoint for 30-day average (SOFR30DAYAVG)
 #[derive(Debug, Deserialize)]
@@ -60,17 +61,23 @@ impl CSVRow for FredOvernightRow {
 pub struct Fred;
 
 impl Fred {
-    fn url(date: NaiveDate) -> String {
-        let base_url = "https://fred.stlouisfed.org/graph/fredgraph.csv?id=SOFR30DAYAVG";
+    /// Comma-separated FRED endpoints (primary first), e.g. a caching proxy then the public API
+    pub const ENDPOINTS_ENV: &'static str = "FRED_ENDPOINTS";
+    const DEFAULT_ENDPOINT: &'static str = "https://fred.stlouisfed.org";
+
+    fn endpoints() -> Result<Vec<String>> {
+        endpoints::from_env(Self::ENDPOINTS_ENV, Self::DEFAULT_ENDPOINT)
+    }
+
+    fn path(date: NaiveDate) -> String {
         let (start, end) = window(date, DEFAULT_LOOKBACK_WINDOW);
-        format!("{base_url}&cosd={start}&coed={end}")
+        format!("/graph/fredgraph.csv?id=SOFR30DAYAVG&cosd={start}&coed={end}")
     }
 
-    fn overnight_url(date: NaiveDate) -> String {
-        let base_url = "https://fred.stlouisfed.org/graph/fredgraph.csv?id=SOFR";
+    fn overnight_path(date: NaiveDate) -> String {
         // Need 45 days lookback to ensure we have enough data for 30-day average computation
         let (start, end) = window(date, 45);
-        format!("{base_url}&cosd={start}&coed={end}")
+        format!("/graph/fredgraph.csv?id=SOFR&cosd={start}&coed={end}")
     }
 
     /// Fetch overnight SOFR rates (not the pre-calculated averages)
@@ -78,28 +85,9 @@ impl Fred {
     ///
     /// This is used in addition to standard `Source::fetch` to doubly verify
     /// computed average rate with collected average rate
-    pub fn fetch_overnight_rates(
-        date: NaiveDate,
-    ) -> Result<std::collections::BTreeMap<NaiveDate, u64>> {
-        use csv::{ReaderBuilder, Trim};
-        use std::collections::BTreeMap;
-
-        let body = get_bytes(&Self::overnight_url(date))?;
-        let mut reader = ReaderBuilder::new()
-            .has_headers(true)
-            .flexible(false)
-            .trim(Trim::All)
-            .from_reader(&body[..]);
-
-        let mut rates = BTreeMap::new();
-        for result in reader.deserialize::<FredOvernightRow>() {
-            let row = result?;
-            if row.has_value() {
-                rates.insert(row.date(), row.value());
-            }
-        }
-
-        Ok(rates)
+    pub fn fetch_overnight_rates(date: NaiveDate) -> Result<BTreeMap<NaiveDate, u64>> {
+        let fetched = get_from(&Self::endpoints()?, &Self::overnight_path(date))?;
+        parse_csv_series::<FredOvernightRow>(&fetched.body)
     }
 }
 
@@ -108,8 +96,8 @@ impl Source for Fred {
         "St. Louis FRED"
     }
 
-    fn fetch(&self, date: NaiveDate) -> Result<Vec<u8>> {
-        get_bytes(&Self::url(date))
+    fn fetch(&self, date: NaiveDate) -> Result<Fetched> {
+        get_from(&Self::endpoints()?, &Self::path(date))
     }
 
     fn parse(&self, body: &[u8]) -> Result<(NaiveDate, u64)> {
@@ -0,0 +1,576 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+
+/// Number of recent deviations from consensus kept per source
+pub const DEVIATION_HISTORY: usize = 7;
+
+/// Sources a run needs for a validated median; open breakers never skip below this
+pub const MIN_SOURCES: usize = 2;
+
+/// Circuit breaker thresholds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakerConfig {
+    /// Consecutive failures that open the breaker
+    pub failure_threshold: u32,
+    /// How long an open breaker skips the source before allowing a trial fetch
+    pub cooldown: Duration,
+}
+
+impl Default for BreakerConfig {
+    /// Open after 3 consecutive failures, retry once every 3 days
+    /// Dev: the daemon runs daily, so this skips a failing source for two runs
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            cooldown: Duration::from_secs(3 * 24 * 60 * 60),
+        }
+    }
+}
+
+impl BreakerConfig {
+    /// Build config from environment variables, falling back to defaults:
+    /// `SOURCE_BREAKER_FAILURE_THRESHOLD`, `SOURCE_BREAKER_COOLDOWN_SECS`
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        if let Ok(v) = std::env::var("SOURCE_BREAKER_FAILURE_THRESHOLD") {
+            config.failure_threshold = v
+                .trim()
+                .parse()
+                .context("Invalid SOURCE_BREAKER_FAILURE_THRESHOLD")?;
+        }
+        if let Ok(v) = std::env::var("SOURCE_BREAKER_COOLDOWN_SECS") {
+            config.cooldown = Duration::from_secs(
+                v.trim()
+                    .parse()
+                    .context("Invalid SOURCE_BREAKER_COOLDOWN_SECS")?,
+            );
+        }
+        if config.failure_threshold == 0 {
+            bail!("SOURCE_BREAKER_FAILURE_THRESHOLD must be at least 1");
+        }
+        Ok(config)
+    }
+}
+
+/// Circuit breaker state of a source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Source is fetched normally
+    Closed,
+    /// Source is skipped until the cooldown started at `since` has elapsed
+    Open { since: Instant },
+    /// Cooldown elapsed; the next fetch is a trial that closes or re-opens the breaker
+    HalfOpen,
+}
+
+/// Health of a single source, maintained across runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceHealth {
+    pub state: BreakerState,
+    pub consecutive_failures: u32,
+    pub successes: u64,
+    pub failures: u64,
+    /// Fetches skipped by the open breaker
+    pub skipped: u64,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    /// Total latency of successful fetches, for `average_latency`
+    total_latency: Duration,
+    /// Recent absolute deviations from the consensus median (scaled, 1% = 1_000_000)
+    pub deviations: VecDeque<u64>,
+}
+
+impl Default for SourceHealth {
+    fn default() -> Self {
+        Self {
+            state: BreakerState::Closed,
+            consecutive_failures: 0,
+            successes: 0,
+            failures: 0,
+            skipped: 0,
+            last_success: None,
+            last_error: None,
+            total_latency: Duration::ZERO,
+            deviations: VecDeque::with_capacity(DEVIATION_HISTORY),
+        }
+    }
+}
+
+impl SourceHealth {
+    /// Mean latency of successful fetches
+    pub fn average_latency(&self) -> Option<Duration> {
+        let successes = u32::try_from(self.successes).ok().filter(|n| *n > 0)?;
+        Some(self.total_latency / successes)
+    }
+
+    /// Mean of the recent deviations from consensus
+    pub fn mean_deviation(&self) -> Option<u64> {
+        let n = self.deviations.len() as u64;
+        (n > 0).then(|| self.deviations.iter().sum::<u64>() / n)
+    }
+
+    /// Whether the source may be fetched at `now`; moves an expired open breaker to half-open
+    fn allow(&mut self, config: &BreakerConfig, now: Instant) -> bool {
+        match self.state {
+            BreakerState::Closed | BreakerState::HalfOpen => true,
+            BreakerState::Open { since } => {
+                if now.saturating_duration_since(since) >= config.cooldown {
+                    self.state = BreakerState::HalfOpen;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    fn record_success(&mut self, latency: Duration) {
+        self.state = BreakerState::Closed;
+        self.consecutive_failures = 0;
+        self.successes += 1;
+        self.total_latency += latency;
+        self.last_success = Some(Utc::now());
+    }
+
+    fn record_failure(&mut self, config: &BreakerConfig, error: String, now: Instant) {
+        self.consecutive_failures += 1;
+        self.failures += 1;
+        self.last_error = Some(error);
+        // A failed trial re-opens immediately; otherwise open once the threshold is hit
+        if self.state == BreakerState::HalfOpen
+            || self.consecutive_failures >= config.failure_threshold
+        {
+            self.state = BreakerState::Open { since: now };
+        }
+    }
+
+    fn record_deviation(&mut self, deviation: u64) {
+        if self.deviations.len() == DEVIATION_HISTORY {
+            self.deviations.pop_front();
+        }
+        self.deviations.push_back(deviation);
+    }
+}
+
+/// Sources to fetch in one run, from `HealthRegistry::admit`
+///
+/// Admitted sources are fetched first. Each failure brings in the next source the open
+/// breaker skipped, in order, so a healthy skipped source can still save the run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Admission {
+    pending: VecDeque<&'static str>,
+    skipped: VecDeque<&'static str>,
+}
+
+impl Admission {
+    /// Next source to fetch, if any
+    pub fn next_source(&mut self) -> Option<&'static str> {
+        self.pending.pop_front()
+    }
+
+    /// Queue the first skipped source in place of `failed`, if any is left
+    pub fn replace_failed(&mut self, failed: &str) {
+        if let Some(source) = self.skipped.pop_front() {
+            warn!("{failed} failed; fetching {source} despite its open circuit breaker");
+            self.pending.push_back(source);
+        }
+    }
+
+    /// Sources skipped so far, in order
+    pub fn skipped(&self) -> Vec<&'static str> {
+        self.skipped.iter().copied().collect()
+    }
+}
+
+/// Health of all sources, keyed by `Source::name`
+///
+/// Health is tracked in every process, but the circuit breaker only skips sources once
+/// `enable_breaker` is called. State lives in memory, so only the long-running daemon
+/// carries it across runs; one-shot commands always fetch every source.
+#[derive(Debug, Default)]
+pub struct HealthRegistry {
+    config: BreakerConfig,
+    breaker_enabled: bool,
+    sources: BTreeMap<&'static str, SourceHealth>,
+}
+
+impl HealthRegistry {
+    pub fn new(config: BreakerConfig) -> Self {
+        Self {
+            config,
+            breaker_enabled: false,
+            sources: BTreeMap::new(),
+        }
+    }
+
+    /// Let open breakers skip sources (the daemon, which queries the current date daily)
+    pub fn enable_breaker(&mut self) {
+        self.breaker_enabled = true;
+    }
+
+    /// Plan which of `sources` to fetch at `now`
+    ///
+    /// Sources with an open breaker are skipped, unless that would leave fewer than
+    /// `MIN_SOURCES`; then skipped sources are admitted anyway, in order. The rest stay
+    /// in reserve for `Admission::replace_failed`. An expired cooldown admits the source
+    /// for a single trial fetch.
+    pub fn admit(&mut self, sources: &[&'static str], now: Instant) -> Admission {
+        if !self.breaker_enabled {
+            return Admission {
+                pending: sources.iter().copied().collect(),
+                skipped: VecDeque::new(),
+            };
+        }
+        let config = self.config;
+        let allowed: Vec<bool> = sources
+            .iter()
+            .map(|source| {
+                let health = self.sources.entry(source).or_default();
+                let allowed = health.allow(&config, now);
+                if health.state == BreakerState::HalfOpen {
+                    info!("{source}: circuit breaker cooldown elapsed, trying source again");
+                }
+                allowed
+            })
+            .collect();
+        let mut shortfall = MIN_SOURCES.saturating_sub(allowed.iter().filter(|a| **a).count());
+        let mut admission = Admission::default();
+        for (source, allowed) in sources.iter().zip(allowed) {
+            if allowed {
+                admission.pending.push_back(source);
+            } else if shortfall > 0 {
+                shortfall -= 1;
+                warn!(
+                    "{source}: circuit breaker open, fetching anyway to keep {MIN_SOURCES} sources"
+                );
+                admission.pending.push_back(source);
+            } else {
+                warn!("{source} skipped: circuit breaker open");
+                admission.skipped.push_back(source);
+            }
+        }
+        admission
+    }
+
+    /// Record the outcome (fetch latency or error) of every source fetched in one run,
+    /// and count a skip for each source in `skipped`
+    ///
+    /// If every fetched source failed, the cause is most likely shared (our own network,
+    /// or a date no source serves), so failures are logged but not counted against any
+    /// source's breaker.
+    pub fn record_run(
+        &mut self,
+        outcomes: Vec<(&'static str, Result<Duration, String>)>,
+        skipped: &[&'static str],
+        now: Instant,
+    ) {
+        for source in skipped {
+            self.sources.entry(source).or_default().skipped += 1;
+        }
+        let all_failed = outcomes.iter().all(|(_, outcome)| outcome.is_err());
+        if all_failed && !outcomes.is_empty() {
+            warn!("Every source failed; not counting failures against circuit breakers");
+        }
+        for (source, outcome) in outcomes {
+            match outcome {
+                Ok(latency) => self.record_success(source, latency),
+                Err(error) if all_failed => {
+                    self.sources.entry(source).or_default().last_error = Some(error);
+                }
+                Err(error) => self.record_failure(source, error, now),
+            }
+        }
+    }
+
+    pub fn record_success(&mut self, source: &'static str, latency: Duration) {
+        let health = self.sources.entry(source).or_default();
+        if health.state != BreakerState::Closed {
+            info!("{source}: circuit breaker closed after successful fetch");
+        }
+        health.record_success(latency);
+    }
+
+    /// Count a failure; opens the breaker at the threshold only if the breaker is enabled
+    pub fn record_failure(&mut self, source: &'static str, error: String, now: Instant) {
+        let config = self.config;
+        let enabled = self.breaker_enabled;
+        let health = self.sources.entry(source).or_default();
+        let was_open = matches!(health.state, BreakerState::Open { .. });
+        if !enabled {
+            health.consecutive_failures += 1;
+            health.failures += 1;
+            health.last_error = Some(error);
+            return;
+        }
+        health.record_failure(&config, error, now);
+        if !was_open && matches!(health.state, BreakerState::Open { .. }) {
+            warn!(
+                "{source}: circuit breaker opened after {} consecutive failure(s), skipping for {}s",
+                health.consecutive_failures,
+                config.cooldown.as_secs()
+            );
+        }
+    }
+
+    /// Record each source's absolute deviation from the consensus `median`
+    pub fn record_consensus(&mut self, results: &[(&'static str, u64)], median: u64) {
+        for (source, value) in results {
+            self.sources
+                .entry(source)
+                .or_default()
+                .record_deviation(value.abs_diff(median));
+        }
+    }
+
+    /// Health of `source`, if it has been seen
+    pub fn get(&self, source: &str) -> Option<&SourceHealth> {
+        self.sources.get(source)
+    }
+
+    /// Log a one-line health summary per source
+    pub fn log_summary(&self) {
+        for (source, health) in &self.sources {
+            info!(
+                "{source} health: {:?}, {} consecutive failure(s), {} ok / {} failed / {} skipped, last success {}, avg latency {}, mean deviation {}",
+                health.state,
+                health.consecutive_failures,
+                health.successes,
+                health.failures,
+                health.skipped,
+                health
+                    .last_success
+                    .map_or("never".to_string(), |t| t.to_rfc3339()),
+                health
+                    .average_latency()
+                    .map_or("n/a".to_string(), |l| format!("{}ms", l.as_millis())),
+                health
+                    .mean_deviation()
+                    .map_or("n/a".to_string(), |d| d.to_string()),
+            );
+        }
+    }
+}
+
+/// Process-wide registry shared by every run
+static REGISTRY: OnceLock<Mutex<HealthRegistry>> = OnceLock::new();
+
+/// Lock the process-wide registry, configured from the environment on first use
+/// Dev: an invalid breaker configuration falls back to defaults with a warning
+pub fn registry() -> MutexGuard<'static, HealthRegistry> {
+    REGISTRY
+        .get_or_init(|| {
+            let config = BreakerConfig::from_env().unwrap_or_else(|e| {
+                warn!("{e:#}; using default circuit breaker configuration");
+                BreakerConfig::default()
+            });
+            Mutex::new(HealthRegistry::new(config))
+        })
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Let open breakers skip sources in the process-wide registry
+pub fn enable_breaker() {
+    registry().enable_breaker();
+}
+
+/// Snapshot of every tracked source's health
+pub fn snapshot() -> BTreeMap<&'static str, SourceHealth> {
+    registry().sources.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry(threshold: u32, cooldown_secs: u64) -> HealthRegistry {
+        let mut registry = HealthRegistry::new(BreakerConfig {
+            failure_threshold: threshold,
+            cooldown: Duration::from_secs(cooldown_secs),
+        });
+        registry.enable_breaker();
+        registry
+    }
+
+    const SOURCES: [&str; 3] = ["FRED", "NY Fed", "OFR"];
+
+    fn is_open(reg: &HealthRegistry, source: &str) -> bool {
+        matches!(reg.get(source).unwrap().state, BreakerState::Open { .. })
+    }
+
+    /// Sources admitted up front at `now`, counting the rest as skipped
+    fn admitted(reg: &mut HealthRegistry, now: Instant) -> Vec<&'static str> {
+        let mut admission = reg.admit(&SOURCES, now);
+        let admitted = std::iter::from_fn(|| admission.next_source()).collect();
+        reg.record_run(Vec::new(), &admission.skipped(), now);
+        admitted
+    }
+
+    #[test]
+    fn opens_after_threshold_and_skips() {
+        let mut reg = registry(3, 60);
+        let now = Instant::now();
+        for _ in 0..2 {
+            assert!(admitted(&mut reg, now).contains(&"FRED"));
+            reg.record_failure("FRED", "boom".to_string(), now);
+        }
+        assert_eq!(reg.get("FRED").unwrap().state, BreakerState::Closed);
+
+        assert!(admitted(&mut reg, now).contains(&"FRED"));
+        reg.record_failure("FRED", "boom".to_string(), now);
+        assert!(is_open(&reg, "FRED"));
+        assert_eq!(
+            admitted(&mut reg, now + Duration::from_secs(59)),
+            ["NY Fed", "OFR"]
+        );
+        assert_eq!(reg.get("FRED").unwrap().skipped, 1);
+    }
+
+    #[test]
+    fn half_open_trial_closes_or_reopens() {
+        let mut reg = registry(1, 60);
+        let now = Instant::now();
+        reg.record_failure("OFR", "boom".to_string(), now);
+
+        // Failed trial re-opens with a fresh cooldown
+        let later = now + Duration::from_secs(60);
+        assert_eq!(admitted(&mut reg, later), SOURCES);
+        assert_eq!(reg.get("OFR").unwrap().state, BreakerState::HalfOpen);
+        reg.record_failure("OFR", "boom".to_string(), later);
+        assert_eq!(
+            reg.get("OFR").unwrap().state,
+            BreakerState::Open { since: later }
+        );
+        assert_eq!(
+            admitted(&mut reg, later + Duration::from_secs(30)),
+            ["FRED", "NY Fed"]
+        );
+
+        // Successful trial closes
+        let much_later = later + Duration::from_secs(60);
+        assert_eq!(admitted(&mut reg, much_later), SOURCES);
+        reg.record_success("OFR", Duration::from_millis(10));
+        let health = reg.get("OFR").unwrap();
+        assert_eq!(health.state, BreakerState::Closed);
+        assert_eq!(health.consecutive_failures, 0);
+        assert!(health.last_success.is_some());
+    }
+
+    #[test]
+    fn tracks_latency_and_deviation() {
+        let mut reg = registry(3, 60);
+        reg.record_success("NY Fed", Duration::from_millis(100));
+        reg.record_success("NY Fed", Duration::from_millis(300));
+        assert_eq!(
+            reg.get("NY Fed").unwrap().average_latency(),
+            Some(Duration::from_millis(200))
+        );
+
+        for i in 0..10 {
+            reg.record_consensus(&[("NY Fed", 4_000_000 + i), ("FRED", 4_000_000)], 4_000_000);
+        }
+        let health = reg.get("NY Fed").unwrap();
+        assert_eq!(health.deviations.len(), DEVIATION_HISTORY);
+        // Last 7 of 0..10
+        assert_eq!(health.mean_deviation(), Some(6));
+        assert_eq!(reg.get("FRED").unwrap().mean_deviation(), Some(0));
+    }
+
+    #[test]
+    fn never_skips_below_min_sources() {
+        let mut reg = registry(1, 60);
+        let now = Instant::now();
+        reg.record_failure("FRED", "boom".to_string(), now);
+        assert_eq!(admitted(&mut reg, now), ["NY Fed", "OFR"]);
+        assert_eq!(reg.get("FRED").unwrap().skipped, 1);
+
+        // With two breakers open, the first is fetched anyway to keep two sources
+        reg.record_failure("NY Fed", "boom".to_string(), now);
+        assert_eq!(admitted(&mut reg, now), ["FRED", "OFR"]);
+        assert_eq!(reg.get("FRED").unwrap().skipped, 1);
+        assert_eq!(reg.get("NY Fed").unwrap().skipped, 1);
+    }
+
+    #[test]
+    fn skipped_source_replaces_failed_one() {
+        let mut reg = registry(1, 60);
+        let now = Instant::now();
+        reg.record_failure("FRED", "boom".to_string(), now);
+
+        // NY Fed fails, so the skipped (but by now healthy) FRED is fetched in its place
+        let mut admission = reg.admit(&SOURCES, now);
+        let mut outcomes = Vec::new();
+        let mut fetched = Vec::new();
+        while let Some(source) = admission.next_source() {
+            fetched.push(source);
+            if source == "NY Fed" {
+                outcomes.push((source, Err("timeout".to_string())));
+                admission.replace_failed(source);
+            } else {
+                outcomes.push((source, Ok(Duration::from_millis(5))));
+            }
+        }
+        assert_eq!(fetched, ["NY Fed", "OFR", "FRED"]);
+        assert!(admission.skipped().is_empty());
+
+        reg.record_run(outcomes, &admission.skipped(), now);
+        let fred = reg.get("FRED").unwrap();
+        assert_eq!(fred.state, BreakerState::Closed);
+        assert_eq!(fred.skipped, 0);
+        assert!(is_open(&reg, "NY Fed"));
+
+        // Each skipped source replaces at most one failure
+        let mut admission = reg.admit(&SOURCES, now);
+        assert_eq!(admission.skipped(), ["NY Fed"]);
+        admission.replace_failed("FRED");
+        admission.replace_failed("OFR");
+        assert!(admission.skipped().is_empty());
+        assert_eq!(
+            std::iter::from_fn(|| admission.next_source()).collect::<Vec<_>>(),
+            ["FRED", "OFR", "NY Fed"]
+        );
+    }
+
+    #[test]
+    fn shared_failures_are_not_counted() {
+        let mut reg = registry(1, 60);
+        let now = Instant::now();
+        let down = |source| (source, Err("connection refused".to_string()));
+        reg.record_run(vec![down("FRED"), down("NY Fed"), down("OFR")], &[], now);
+        for source in ["FRED", "NY Fed", "OFR"] {
+            let health = reg.get(source).unwrap();
+            assert_eq!(health.state, BreakerState::Closed);
+            assert_eq!(health.consecutive_failures, 0);
+            assert_eq!(health.last_error.as_deref(), Some("connection refused"));
+        }
+
+        // A failure while other sources succeed still counts
+        let ok = |source| (source, Ok(Duration::from_millis(5)));
+        reg.record_run(vec![down("FRED"), ok("NY Fed"), ok("OFR")], &[], now);
+        assert!(is_open(&reg, "FRED"));
+        assert!(!is_open(&reg, "NY Fed"));
+    }
+
+    #[test]
+    fn disabled_breaker_never_opens() {
+        let mut reg = HealthRegistry::new(BreakerConfig {
+            failure_threshold: 1,
+            cooldown: Duration::from_secs(60),
+        });
+        let now = Instant::now();
+        reg.record_failure("FRED", "boom".to_string(), now);
+        reg.record_failure("FRED", "boom".to_string(), now);
+        let health = reg.get("FRED").unwrap();
+        assert_eq!(health.state, BreakerState::Closed);
+        assert_eq!(health.consecutive_failures, 2);
+        let mut admission = reg.admit(&["FRED", "OFR"], now);
+        assert_eq!(admission.next_source(), Some("FRED"));
+        assert_eq!(admission.next_source(), Some("OFR"));
+        assert!(admission.skipped().is_empty());
+    }
+}
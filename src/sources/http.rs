@@ -0,0 +1,419 @@
+use std::fmt;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+use std::{env, fs};
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use reqwest::blocking::Client;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Certificate, Proxy, StatusCode};
+
+/// Default User-Agent sent with every source request
+pub const DEFAULT_USER_AGENT: &str = concat!("aqa-publisher/", env!("CARGO_PKG_VERSION"));
+
+/// Process-wide source HTTP client, built once on first use
+static CLIENT: OnceLock<HttpClient> = OnceLock::new();
+
+/// Configuration of the HTTP client shared by all source fetches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpConfig {
+    /// Maximum time to establish a connection
+    pub connect_timeout: Duration,
+    /// Maximum time for a whole request, including reading the response body
+    pub timeout: Duration,
+    /// User-Agent header, ideally identifying the validator operating the publisher
+    pub user_agent: String,
+    /// HTTPS proxy URL all source requests are sent through
+    pub https_proxy: Option<String>,
+    /// Extra PEM root certificates to trust (e.g., a corporate egress proxy CA)
+    pub root_certificates: Vec<PathBuf>,
+    /// Responses larger than this are rejected
+    pub max_response_bytes: u64,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            timeout: Duration::from_secs(30),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            https_proxy: None,
+            root_certificates: Vec::new(),
+            max_response_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Build configuration from environment variables, falling back to defaults:
+    /// - `SOURCE_HTTP_CONNECT_TIMEOUT_SECS`, `SOURCE_HTTP_TIMEOUT_SECS`
+    /// - `SOURCE_HTTP_USER_AGENT`
+    /// - `SOURCE_HTTPS_PROXY`
+    /// - `SOURCE_HTTP_CA_CERTS` (comma-separated PEM file paths)
+    /// - `SOURCE_HTTP_MAX_RESPONSE_BYTES`
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        if let Some(secs) = env_parse::<u64>("SOURCE_HTTP_CONNECT_TIMEOUT_SECS")? {
+            config.connect_timeout = Duration::from_secs(secs);
+        }
+        if let Some(secs) = env_parse::<u64>("SOURCE_HTTP_TIMEOUT_SECS")? {
+            config.timeout = Duration::from_secs(secs);
+        }
+        if let Ok(user_agent) = env::var("SOURCE_HTTP_USER_AGENT") {
+            config.user_agent = user_agent;
+        }
+        if let Ok(proxy) = env::var("SOURCE_HTTPS_PROXY") {
+            config.https_proxy = Some(proxy);
+        }
+        if let Ok(paths) = env::var("SOURCE_HTTP_CA_CERTS") {
+            config.root_certificates = paths
+                .split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(PathBuf::from)
+                .collect();
+        }
+        if let Some(bytes) = env_parse::<u64>("SOURCE_HTTP_MAX_RESPONSE_BYTES")? {
+            config.max_response_bytes = bytes;
+        }
+        Ok(config)
+    }
+}
+
+/// Parse an optional environment variable
+fn env_parse<T>(name: &str) -> Result<Option<T>>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    match env::var(name) {
+        Ok(value) => value
+            .trim()
+            .parse()
+            .map(Some)
+            .with_context(|| format!("Invalid {name}: '{value}'")),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Failure of a single source `GET`, classified for retry decisions
+#[derive(Debug)]
+pub enum FetchError {
+    /// Connecting or reading the response timed out
+    Timeout(String),
+    /// Connection could not be established
+    Connect(String),
+    /// Server answered with a non-success status
+    Status {
+        url: String,
+        status: StatusCode,
+        retry_after: Option<Duration>,
+    },
+    /// Response body exceeded the configured maximum size
+    TooLarge { url: String, max: u64 },
+    /// Reading the response body failed
+    Body(String),
+    /// Any other failure (invalid URL, redirect loop, ...)
+    Other(String),
+}
+
+impl FetchError {
+    fn from_reqwest(url: &str, e: reqwest::Error) -> Self {
+        let message = format!("GET {url}: {e}");
+        if e.is_timeout() {
+            FetchError::Timeout(message)
+        } else if e.is_connect() {
+            FetchError::Connect(message)
+        } else {
+            FetchError::Other(message)
+        }
+    }
+
+    /// Whether retrying could succeed: timeouts, connection and body read errors,
+    /// 5xx, 408 and 429 responses
+    pub fn is_transient(&self) -> bool {
+        match self {
+            FetchError::Timeout(_) | FetchError::Connect(_) | FetchError::Body(_) => true,
+            FetchError::Status { status, .. } => {
+                status.is_server_error()
+                    || *status == StatusCode::TOO_MANY_REQUESTS
+                    || *status == StatusCode::REQUEST_TIMEOUT
+            }
+            FetchError::TooLarge { .. } | FetchError::Other(_) => false,
+        }
+    }
+
+    /// Server-requested delay before retrying, from a `Retry-After` header
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            FetchError::Status { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Timeout(message)
+            | FetchError::Connect(message)
+            | FetchError::Other(message) => write!(f, "{message}"),
+            FetchError::Status { url, status, .. } => write!(f, "status {status} for {url}"),
+            FetchError::TooLarge { url, max } => {
+                write!(f, "response from {url} exceeds max {max} bytes")
+            }
+            FetchError::Body(message) => write!(f, "reading body: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+/// Parse a `Retry-After` header value: delay in seconds or an HTTP date
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (at.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Blocking HTTP client with connection reuse, shared by all sources
+#[derive(Debug)]
+pub struct HttpClient {
+    client: Client,
+    /// Configured request timeout, kept to cap per-attempt limits against
+    timeout: Duration,
+    max_response_bytes: u64,
+}
+
+impl HttpClient {
+    pub fn new(config: &HttpConfig) -> Result<Self> {
+        let mut builder = Client::builder()
+            .connect_timeout(config.connect_timeout)
+            .timeout(config.timeout)
+            .user_agent(&config.user_agent);
+
+        if let Some(proxy) = &config.https_proxy {
+            builder = builder.proxy(
+                Proxy::https(proxy).with_context(|| format!("Invalid HTTPS proxy '{proxy}'"))?,
+            );
+        }
+        for path in &config.root_certificates {
+            let pem = fs::read(path)
+                .with_context(|| format!("Failed to read root certificate {}", path.display()))?;
+            let certificate = Certificate::from_pem(&pem)
+                .with_context(|| format!("Invalid root certificate {}", path.display()))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        Ok(Self {
+            client: builder
+                .build()
+                .context("Failed to build source HTTP client")?,
+            timeout: config.timeout,
+            max_response_bytes: config.max_response_bytes,
+        })
+    }
+
+    /// Single `GET` attempt returning the response body, bounded by `max_response_bytes`
+    pub fn get(&self, url: &str) -> Result<Vec<u8>, FetchError> {
+        self.get_with_timeout(url, None)
+    }
+
+    /// Single `GET` attempt, with the configured request timeout shortened to `limit` if given
+    pub fn get_with_timeout(
+        &self,
+        url: &str,
+        limit: Option<Duration>,
+    ) -> Result<Vec<u8>, FetchError> {
+        let mut request = self.client.get(url);
+        if let Some(limit) = limit {
+            request = request.timeout(self.timeout.min(limit));
+        }
+        let resp = request
+            .send()
+            .map_err(|e| FetchError::from_reqwest(url, e))?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(FetchError::Status {
+                url: url.to_string(),
+                status,
+                retry_after: resp
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(parse_retry_after),
+            });
+        }
+
+        // Reject early on a declared oversized body, then enforce while reading
+        let too_large = || FetchError::TooLarge {
+            url: url.to_string(),
+            max: self.max_response_bytes,
+        };
+        if resp
+            .content_length()
+            .is_some_and(|length| length > self.max_response_bytes)
+        {
+            return Err(too_large());
+        }
+        let mut body = Vec::new();
+        resp.take(self.max_response_bytes + 1)
+            .read_to_end(&mut body)
+            .map_err(|e| FetchError::Body(e.to_string()))?;
+        if body.len() as u64 > self.max_response_bytes {
+            return Err(too_large());
+        }
+        Ok(body)
+    }
+}
+
+/// Configure the shared source HTTP client; must be called before the first fetch
+pub fn init(config: &HttpConfig) -> Result<()> {
+    CLIENT
+        .set(HttpClient::new(config)?)
+        .map_err(|_| anyhow!("source HTTP client already initialized"))
+}
+
+/// Shared source HTTP client, built from `HttpConfig::from_env` if not yet initialized
+pub fn client() -> Result<&'static HttpClient> {
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+    let client = HttpClient::new(&HttpConfig::from_env()?)?;
+    Ok(CLIENT.get_or_init(|| client))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_http::{response, serve};
+    use std::net::TcpListener;
+
+    /// Serve a single `200 OK` response with `body` on a local port
+    fn serve_once(body: &str) -> String {
+        serve(vec![response("200 OK", "", body)]).0
+    }
+
+    /// Serve a single empty response with `status` and extra header lines
+    fn serve_status(status: &str, headers: &str) -> String {
+        serve(vec![response(status, headers, "")]).0
+    }
+
+    #[test]
+    fn default_user_agent_has_version() {
+        assert!(
+            HttpConfig::default()
+                .user_agent
+                .starts_with("aqa-publisher/")
+        );
+    }
+
+    #[test]
+    fn fetches_body() {
+        let client = HttpClient::new(&HttpConfig::default()).unwrap();
+        assert_eq!(client.get(&serve_once("hello")).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn rejects_oversized_body() {
+        let client = HttpClient::new(&HttpConfig {
+            max_response_bytes: 4,
+            ..HttpConfig::default()
+        })
+        .unwrap();
+        let err = client.get(&serve_once("hello")).unwrap_err();
+        assert!(matches!(err, FetchError::TooLarge { max: 4, .. }));
+        assert!(!err.is_transient());
+    }
+
+    #[test]
+    fn classifies_status_errors() {
+        let client = HttpClient::new(&HttpConfig::default()).unwrap();
+
+        let not_found = client.get(&serve_status("404 Not Found", "")).unwrap_err();
+        assert!(!not_found.is_transient());
+
+        let unavailable = client
+            .get(&serve_status("503 Service Unavailable", ""))
+            .unwrap_err();
+        assert!(unavailable.is_transient());
+        assert_eq!(unavailable.retry_after(), None);
+
+        let limited = client
+            .get(&serve_status("429 Too Many Requests", "Retry-After: 7\r\n"))
+            .unwrap_err();
+        assert!(limited.is_transient());
+        assert_eq!(limited.retry_after(), Some(Duration::from_secs(7)));
+    }
+
+    #[test]
+    fn classifies_connection_errors() {
+        // Bind then drop a listener to get a port nothing is listening on
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let client = HttpClient::new(&HttpConfig::default()).unwrap();
+        let err = client.get(&format!("http://{addr}/")).unwrap_err();
+        assert!(matches!(err, FetchError::Connect(_)), "{err:?}");
+        assert!(err.is_transient());
+    }
+
+    #[test]
+    fn keeps_configured_timeout_under_longer_limit() {
+        // Accepted by the OS backlog but never answered
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let client = HttpClient::new(&HttpConfig {
+            timeout: Duration::from_millis(200),
+            ..HttpConfig::default()
+        })
+        .unwrap();
+
+        let start = std::time::Instant::now();
+        let err = client
+            .get_with_timeout(&url, Some(Duration::from_secs(60)))
+            .unwrap_err();
+        assert!(matches!(err, FetchError::Timeout(_)), "{err:?}");
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn parses_retry_after() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn rejects_invalid_proxy_and_certificates() {
+        assert!(
+            HttpClient::new(&HttpConfig {
+                https_proxy: Some("not a url".to_string()),
+                ..HttpConfig::default()
+            })
+            .is_err()
+        );
+        assert!(
+            HttpClient::new(&HttpConfig {
+                root_certificates: vec![PathBuf::from("/nonexistent/ca.pem")],
+                ..HttpConfig::default()
+            })
+            .is_err()
+        );
+    }
+}
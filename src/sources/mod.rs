@@ -1,73 +1,86 @@
+pub mod cassette;
+pub mod compounding;
 pub(crate) mod csv;
 pub(crate) mod de;
+pub mod endpoints;
 pub mod fred;
+pub mod health;
+pub mod http;
 pub mod nyfed;
 pub mod ofr;
+pub mod retry;
 
-use anyhow::{Context, Result, anyhow, bail};
+use anyhow::{Result, anyhow, bail};
 use chrono::{Days, NaiveDate};
-use log::warn;
 use rust_decimal::{Decimal, prelude::ToPrimitive};
 use std::collections::BTreeMap;
 use std::str::FromStr;
 
+use compounding::{CompoundingSpec, compound};
+pub use endpoints::Fetched;
+
 /// Default lookback for data collection window
 pub const DEFAULT_LOOKBACK_WINDOW: u64 = 14;
 
+/// Single value collected from a source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Observation {
+    /// Most recently available date
+    pub date: NaiveDate,
+    /// Scaled value for `date` (1% = 1_000_000)
+    pub value: u64,
+    /// Base URL of the endpoint (primary or mirror) that served the data
+    pub endpoint: String,
+}
+
 /// Common trait implemented by each API data source
 pub trait Source {
     /// Data source name
     fn name(&self) -> &'static str;
 
-    /// Fetch raw response bytes for a small window
+    /// Fetch raw response bytes for a small window from the first working endpoint
     /// Window is [date - 14 days, date] to account for holidays & weekends
-    fn fetch(&self, date: NaiveDate) -> Result<Vec<u8>>;
+    fn fetch(&self, date: NaiveDate) -> Result<Fetched>;
 
     /// Parse fetched data into a single, scaled `u64` value (1e8 scale, 1% = 1_000_000)
     /// Returns (most recently available date, scaled `u64` yield value for said date)
     fn parse(&self, body: &[u8]) -> Result<(NaiveDate, u64)>;
 
+    /// Unified fetch + parse, keeping the serving endpoint
+    fn observe(&self, date: NaiveDate) -> Result<Observation> {
+        let fetched = self.fetch(date)?;
+        let (date, value) = self.parse(&fetched.body)?;
+        Ok(Observation {
+            date,
+            value,
+            endpoint: fetched.endpoint,
+        })
+    }
+
     /// Unified fetch + parse
     fn collect(&self, date: NaiveDate) -> Result<(NaiveDate, u64)> {
-        self.parse(&self.fetch(date)?)
+        self.observe(date).map(|o| (o.date, o.value))
     }
 }
 
-/// Small `GET` helper to fetch data from URL as bytes
-/// Retries up to 3 times with exponential backoff (30s, 60s, 120s) on failures
+/// Small `GET` helper to fetch data from URL as bytes via the shared `http::client`
+/// Transient failures are retried per `RetryPolicy::from_env` (default 3 attempts,
+/// 30s/60s backoff with jitter), bounded by the deadline set with `retry::with_deadline`
+/// Responses are recorded or replayed per the process-wide `cassette::cassette`
 pub(crate) fn get_bytes(url: &str) -> Result<Vec<u8>> {
-    const MAX_RETRIES: u32 = 3;
-    const INITIAL_DELAY_SECS: u64 = 30;
-
-    let mut last_error = None;
-
-    for attempt in 1..=MAX_RETRIES {
-        match reqwest::blocking::get(url)
-            .with_context(|| format!("GET {url}"))
-            .and_then(|resp| {
-                resp.error_for_status()
-                    .with_context(|| format!("status not OK for {url}"))
-            })
-            .and_then(|resp| resp.bytes().with_context(|| "reading body"))
-        {
-            Ok(bytes) => return Ok(bytes.to_vec()),
-            Err(e) => {
-                last_error = Some(e);
-                if attempt < MAX_RETRIES {
-                    let delay_secs = INITIAL_DELAY_SECS * (2u64.pow(attempt - 1));
-                    warn!(
-                        "Request to {} failed (attempt {}/{}), retrying in {}s...",
-                        url, attempt, MAX_RETRIES, delay_secs
-                    );
-                    std::thread::sleep(std::time::Duration::from_secs(delay_secs));
-                }
-            }
-        }
-    }
+    cassette::cassette()?.fetch(url, || {
+        retry::fetch_with_retry(
+            http::client()?,
+            url,
+            &retry::RetryPolicy::from_env()?,
+            retry::current_deadline(),
+        )
+    })
+}
 
-    Err(last_error
-        .unwrap()
-        .context(format!("Failed after {} retries", MAX_RETRIES)))
+/// `GET` `path` from each of `endpoints` in order via `get_bytes` until one succeeds
+pub(crate) fn get_from(endpoints: &[String], path: &str) -> Result<Fetched> {
+    endpoints::fetch_first(endpoints, path, get_bytes)
 }
 
 /// Convert a percent value (e.g., 4.2932) to scaled `u64` (1% == 1_000_000)
@@ -122,16 +135,8 @@ pub fn parse_ymd(s: &str) -> Result<NaiveDate> {
 /// This function is used in conjunction with overnight rates fetched from NYFed and Fred to
 /// doubly verify computed compounded average matches collected compounded average.
 ///
-/// Formula: ((∏(1 + SOFRi × ni/360)) - 1) × 360/dc
-/// where:
-/// - SOFRi = overnight SOFR rate for business day i (as decimal, e.g., 0.04293 for 4.293%)
-/// - ni = number of calendar days for which SOFR_i applies (e.g., 3 for most Fridays)
-/// - dc = number of calendar days in calculation period (30 for 30-day average)
-///
-/// The calculation:
-/// 1. Takes overnight rates in scaled u64 format (1% = 1_000_000)
-/// 2. Compounds once per business day, using ni to account for weekends/holidays
-/// 3. Returns the result in scaled u64 format
+/// Takes overnight rates in scaled u64 format (1% = 1_000_000), compounds them with
+/// `compounding::compound` and returns the floored result in scaled u64 format
 pub fn compute_compounded_average(
     effective_date: NaiveDate,
     overnight_rates: &BTreeMap<NaiveDate, u64>,
@@ -140,84 +145,14 @@ pub fn compute_compounded_average(
         bail!("no overnight rates provided")
     }
 
-    // The effective_date is the publication date of the average.
-    // The calculation period ends one business day earlier (the value date of the last SOFR).
-    // For a 30-day average published on Oct 7:
-    // - Calculation period: Sep 7 to Oct 6 (30 calendar days)
-    let calculation_end_date = effective_date
-        .checked_sub_days(Days::new(1))
-        .ok_or_else(|| anyhow!("date underflow"))?;
-
-    // Start date is 30 days before publication date
-    let start_date = effective_date
-        .checked_sub_days(Days::new(30))
-        .ok_or_else(|| anyhow!("date underflow"))?;
-
-    // Find the rate for the last business day before (or at) start_date
-    let initial_rate = overnight_rates
-        .range(..=start_date)
-        .next_back()
-        .map(|(_, r)| *r)
-        .ok_or_else(|| anyhow!("insufficient history before {start_date}"))?;
-
-    // Build a list of (rate, ni) tuples where ni = number of calendar days this rate applies
-    let mut business_days: Vec<(u64, u64)> = Vec::new();
-
-    let mut current_rate = initial_rate;
-    let mut current_rate_start = start_date; // Track when current rate started applying
-
-    // Iterate through the 30-day calculation period
-    let period_days = calculation_end_date
-        .signed_duration_since(start_date)
-        .num_days() as u64;
-    for day_offset in 0..=period_days {
-        let day = start_date
-            .checked_add_days(Days::new(day_offset))
-            .ok_or_else(|| anyhow!("date overflow"))?;
-
-        // Check if this is a new business day (has a rate)
-        if let Some(&new_rate) = overnight_rates.get(&day) {
-            // Finalize the current rate's ni count (from current_rate_start to day-1)
-            let duration = day.signed_duration_since(current_rate_start);
-            let ni = duration.num_days() as u64;
-            if ni > 0 {
-                business_days.push((current_rate, ni));
-            }
-
-            current_rate = new_rate;
-            current_rate_start = day;
-        }
-    }
+    // Scaled u64 -> exact percent decimal (4_293_200 -> 4.293200)
+    let rates: BTreeMap<NaiveDate, Decimal> = overnight_rates
+        .iter()
+        .map(|(date, rate)| (*date, Decimal::new(*rate as i64, 6)))
+        .collect();
 
-    // Handle the final rate (from current_rate_start to calculation_end_date inclusive)
-    let duration = calculation_end_date.signed_duration_since(current_rate_start);
-    let ni = duration.num_days() as u64 + 1;
-    business_days.push((current_rate, ni));
-
-    // Compound using the ni-grouped approach
-    let one_million = Decimal::from(1_000_000u64);
-    let d360 = Decimal::from(360);
-    let d100 = Decimal::from(100);
-
-    let mut factor = Decimal::ONE;
-
-    for (rate, ni) in business_days {
-        // Convert scaled u64 rate to decimal percentage (1% = 0.01)
-        let rate_decimal = Decimal::from(rate) / one_million / d100;
-
-        // Compound: factor *= (1 + rate × ni/360)
-        let ni_decimal = Decimal::from(ni);
-        factor *= Decimal::ONE + rate_decimal * ni_decimal / d360;
-    }
-
-    // Annualize: ((factor - 1) × 360/30) and convert to percentage then to scaled u64
-    let avg_pct = (factor - Decimal::ONE) * (d360 / Decimal::from(30));
-
-    // Convert back to scaled u64: percentage × 1_000_000
-    let scaled = (avg_pct * d100 * one_million).trunc();
-    scaled
-        .to_u64()
-        .ok_or_else(|| anyhow!("overflow converting to u64"))
+    let avg_pct = compound(effective_date, &rates, &CompoundingSpec::sofr_30d_average())?;
+    percent_to_floored_u64(&avg_pct.to_string())
 }
 
 #[cfg(test)]
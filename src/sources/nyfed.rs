@@ -1,9 +1,10 @@
-use super::csv::{CSVRow, parse_csv_for_latest};
+use super::csv::{CSVRow, parse_csv_for_latest, parse_csv_series};
 use super::de::{de_date, de_scaled, de_scaled_opt};
-use crate::sources::{DEFAULT_LOOKBACK_WINDOW, Source, get_bytes, window};
+use crate::sources::{DEFAULT_LOOKBACK_WINDOW, Fetched, Source, endpoints, get_from, window};
 use anyhow::{Result, anyhow};
 use chrono::NaiveDate;
 use serde::Deserialize;
+use std::collections::BTreeMap;
 
 /// CSV row format returned from NY Fed Markets Data search CSV endpoint (SOFRAI - averages)
 /// Only relevant subset of full set of fields are included, we are not strict matching
@@ -57,17 +58,23 @@ impl CSVRow for NYFedOvernightRow {
 pub struct NYFed;
 
 impl NYFed {
-    fn url(date: NaiveDate) -> String {
-        let base_url = "https://markets.newyorkfed.org/api/rates/secured/sofrai/search.csv";
+    /// Comma-separated NY Fed endpoints (primary first), e.g. a caching proxy then the public API
+    pub const ENDPOINTS_ENV: &'static str = "NYFED_ENDPOINTS";
+    const DEFAULT_ENDPOINT: &'static str = "https://markets.newyorkfed.org";
+
+    fn endpoints() -> Result<Vec<String>> {
+        endpoints::from_env(Self::ENDPOINTS_ENV, Self::DEFAULT_ENDPOINT)
+    }
+
+    fn path(date: NaiveDate) -> String {
         let (start, end) = window(date, DEFAULT_LOOKBACK_WINDOW);
-        format!("{base_url}?type=rate&startDate={start}&endDate={end}")
+        format!("/api/rates/secured/sofrai/search.csv?type=rate&startDate={start}&endDate={end}")
     }
 
-    fn overnight_url(date: NaiveDate) -> String {
-        let base_url = "https://markets.newyorkfed.org/api/rates/secured/sofr/search.csv";
+    fn overnight_path(date: NaiveDate) -> String {
         // Need 45 days lookback to ensure we have enough data for 30-day average computation
         let (start, end) = window(date, 45);
-        format!("{base_url}?startDate={start}&endDate={end}")
+        format!("/api/rates/secured/sofr/search.csv?startDate={start}&endDate={end}")
     }
 
     /// Fetch overnight SOFR rates (not the pre-calculated averages)
@@ -75,28 +82,19 @@ impl NYFed {
     ///
     /// This is used in addition to standard `Source::fetch` to doubly verify
     /// computed average rate with collected average rate
-    pub fn fetch_overnight_rates(
-        date: NaiveDate,
-    ) -> Result<std::collections::BTreeMap<NaiveDate, u64>> {
-        use csv::{ReaderBuilder, Trim};
-        use std::collections::BTreeMap;
-
-        let body = get_bytes(&Self::overnight_url(date))?;
-        let mut reader = ReaderBuilder::new()
-            .has_headers(true)
-            .flexible(false)
-            .trim(Trim::All)
-            .from_reader(&body[..]);
-
-        let mut rates = BTreeMap::new();
-        for result in reader.deserialize::<NYFedOvernightRow>() {
-            let row = result?;
-            if row.has_value() {
-                rates.insert(row.date(), row.value());
-            }
-        }
-
-        Ok(rates)
+    pub fn fetch_overnight_rates(date: NaiveDate) -> Result<BTreeMap<NaiveDate, u64>> {
+        let fetched = get_from(&Self::endpoints()?, &Self::overnight_path(date))?;
+        Self::parse_overnight_rates(&fetched.body)
+    }
+
+    /// Parse a full overnight SOFR `search.csv` body into date -> scaled rate
+    pub fn parse_overnight_rates(body: &[u8]) -> Result<BTreeMap<NaiveDate, u64>> {
+        parse_csv_series::<NYFedOvernightRow>(body)
+    }
+
+    /// Parse a full SOFR Averages `search.csv` body into date -> scaled 30-day average
+    pub fn parse_averages(body: &[u8]) -> Result<BTreeMap<NaiveDate, u64>> {
+        parse_csv_series::<NYFedCSVRow>(body)
     }
 }
 
@@ -105,8 +103,8 @@ impl Source for NYFed {
         "NY Fed"
     }
 
-    fn fetch(&self, date: NaiveDate) -> Result<Vec<u8>> {
-        get_bytes(&Self::url(date))
+    fn fetch(&self, date: NaiveDate) -> Result<Fetched> {
+        get_from(&Self::endpoints()?, &Self::path(date))
     }
 
     /// @dev: we do not do a header check here given far more returned parameters in response
@@ -1,10 +1,11 @@
 use std::collections::BTreeMap;
 
+use super::compounding::{CompoundingSpec, compound};
 use super::de::{de_date, de_decimal2};
-use crate::sources::{Source, get_bytes, window};
+use crate::sources::{Fetched, Source, endpoints, get_from, window};
 use anyhow::{Result, anyhow, bail};
-use chrono::{Days, NaiveDate};
-use rust_decimal::{Decimal, prelude::FromPrimitive};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
 use serde::Deserialize;
 
 /// JSON tuple format returned from OFR FNYR dataset JSON endpoint
@@ -24,21 +25,25 @@ impl OFR {
     // Object: {"mnemonic": "FNYR-SOFR-A", "series_name": "Secured Overnight Financing Rate"}
     const SOFR_MNEMONIC: &'static str = "FNYR-SOFR-A";
 
+    /// Comma-separated OFR endpoints (primary first), e.g. a caching proxy then the public API
+    pub const ENDPOINTS_ENV: &'static str = "OFR_ENDPOINTS";
+    const DEFAULT_ENDPOINT: &'static str = "https://data.financialresearch.gov";
+
+    fn endpoints() -> Result<Vec<String>> {
+        endpoints::from_env(Self::ENDPOINTS_ENV, Self::DEFAULT_ENDPOINT)
+    }
+
     // Fetch ~45 days of data so we can safely carry prior business day rates
-    fn url(date: NaiveDate) -> String {
-        let base_url = "https://data.financialresearch.gov/v1/series/timeseries";
+    fn path(date: NaiveDate) -> String {
         let (start, end) = window(date, 45);
         format!(
-            "{}?mnemonic={}&start_date={}&end_date={}",
-            base_url,
-            Self::SOFR_MNEMONIC,
-            start,
-            end
+            "/v1/series/timeseries?mnemonic={}&start_date={start}&end_date={end}",
+            Self::SOFR_MNEMONIC
         )
     }
 
-    // Compute the NY Fed 30-day compounded SOFR average on calendar days [eff-29, eff]
-    // Using ni-grouped approach: compound once per business day with ni calendar days
+    // Compute the NY Fed 30-day compounded SOFR average ending the day before `effective_date`
+    // Compounding methodology lives in `compounding::compound`
     fn compute_compounded(effective_date: NaiveDate, data: &[OFRTupleRow]) -> Result<Decimal> {
         // Assert some data exists and that at least 30d of data exists
         if data.is_empty() {
@@ -57,69 +62,8 @@ impl OFR {
             bail!("OFR: no business-day observations <= {effective_date}")
         }
 
-        // The effective_date is the publication date of the average.
-        // The calculation period ends one business day earlier (the value date of the last SOFR).
-        // For a 30-day average published on Oct 7:
-        // - Calculation period: Sep 7 to Oct 6 (30 calendar days)
-        let calculation_end_date = effective_date.checked_sub_days(Days::new(1)).unwrap();
-
-        // Start date is 30 days before publication date
-        let start_date = effective_date.checked_sub_days(Days::new(30)).unwrap();
-
-        // Find yield rate of last business day before (or at) `start_date`
-        let initial_rate = map
-            .range(..=start_date)
-            .next_back()
-            .map(|(_, r)| *r)
-            .ok_or_else(|| anyhow!("OFR: insufficient history before {start_date}"))?;
-
-        // Build a list of (rate, ni) tuples where ni = number of calendar days this rate applies
-        let mut business_days: Vec<(Decimal, u64)> = Vec::new();
-
-        let mut current_rate = initial_rate;
-        let mut current_rate_start = start_date; // Track when current rate started applying
-
-        // Iterate through the 30-day calculation period
-        let period_days = calculation_end_date
-            .signed_duration_since(start_date)
-            .num_days() as u64;
-        for day_offset in 0..=period_days {
-            let day = start_date.checked_add_days(Days::new(day_offset)).unwrap();
-
-            // Check if this is a new business day (has a rate)
-            if let Some(&new_rate) = map.get(&day) {
-                // Finalize the current rate's ni count (from current_rate_start to day-1)
-                let duration = day.signed_duration_since(current_rate_start);
-                let ni = duration.num_days() as u64;
-                if ni > 0 {
-                    business_days.push((current_rate, ni));
-                }
-
-                current_rate = new_rate;
-                current_rate_start = day;
-            }
-        }
-
-        // Handle the final rate (from current_rate_start to calculation_end_date inclusive)
-        let duration = calculation_end_date.signed_duration_since(current_rate_start);
-        let ni = duration.num_days() as u64 + 1;
-        business_days.push((current_rate, ni));
-
-        // Compound using the ni-grouped approach
-        let one = Decimal::ONE;
-        let mut factor = Decimal::ONE;
-        let d360 = Decimal::from_i32(360).unwrap();
-        let d100 = Decimal::from_i32(100).unwrap();
-
-        for (rate, ni) in business_days {
-            // Compound: factor *= (1 + rate × ni/360)
-            let ni_decimal = Decimal::from(ni);
-            factor *= one + (rate / d100) * ni_decimal / d360;
-        }
-
-        // Annualize over 30 calendar days and convert to percentage
-        // Result is in decimal form (e.g., 0.04293), multiply by 100 to get percentage (4.293)
-        Ok((factor - one) * (d360 / Decimal::from_i32(30).unwrap()) * d100)
+        compound(effective_date, &map, &CompoundingSpec::sofr_30d_average())
+            .map_err(|e| anyhow!("OFR: {e}"))
     }
 }
 
@@ -128,8 +72,8 @@ impl Source for OFR {
         "OFR (computed)"
     }
 
-    fn fetch(&self, date: NaiveDate) -> Result<Vec<u8>> {
-        get_bytes(&Self::url(date))
+    fn fetch(&self, date: NaiveDate) -> Result<Fetched> {
+        get_from(&Self::endpoints()?, &Self::path(date))
     }
 
     fn parse(&self, body: &[u8]) -> Result<(NaiveDate, u64)> {
@@ -157,7 +101,7 @@ impl Source for OFR {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::{Datelike, NaiveDate};
+    use chrono::{Datelike, Days, NaiveDate};
     use rust_decimal::Decimal;
     use rust_decimal_macros::dec;
     use std::str::FromStr;
@@ -188,7 +132,7 @@ mod tests {
         // Allow small rounding difference due to compounding
         let expected = dec!(4.00);
         let diff = (result - expected).abs();
-        assert!(diff < dec!(0.01), "Expected ~4.00, got {}", result);
+        assert!(diff < dec!(0.01), "Expected ~4.00, got {result}");
     }
 
     #[test]
@@ -216,7 +160,7 @@ mod tests {
         // Should successfully compute even with missing weekend data
         let expected = dec!(4.25);
         let diff = (result - expected).abs();
-        assert!(diff < dec!(0.01), "Expected ~4.25, got {}", result);
+        assert!(diff < dec!(0.01), "Expected ~4.25, got {result}");
     }
 
     #[test]
@@ -270,8 +214,7 @@ mod tests {
         let diff = (result - expected).abs();
         assert!(
             diff < dec!(0.01),
-            "Future dates should be filtered out. Expected ~4.00, got {}",
-            result
+            "Future dates should be filtered out. Expected ~4.00, got {result}"
         );
     }
 
@@ -294,8 +237,7 @@ mod tests {
         // Should be in the range of input rates (4.28 - 4.32)
         assert!(
             result > dec!(4.27) && result < dec!(4.33),
-            "Expected rate in range [4.27, 4.33], got {}",
-            result
+            "Expected rate in range [4.27, 4.33], got {result}"
         );
     }
 
@@ -349,7 +291,7 @@ mod tests {
             ["2025-10-03", 4.29]
         ]"#;
 
-        let ofr = OFR::default();
+        let ofr = OFR;
         let result = ofr.parse(json.as_bytes()).unwrap();
 
         // Should return latest date and a reasonable scaled value
@@ -0,0 +1,322 @@
+use std::cell::Cell;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+use log::warn;
+
+use super::http::{FetchError, HttpClient};
+
+thread_local! {
+    /// Deadline for source fetches on this thread, set via `with_deadline`
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Run `f` with every source fetch on this thread bounded by `deadline`
+///
+/// Sources are fetched synchronously on the calling thread, so this is how a caller
+/// (e.g., the daemon) passes an overall deadline down to `get_bytes` without threading
+/// it through every `Source`. The previous deadline is restored afterwards.
+pub fn with_deadline<T>(deadline: Option<Instant>, f: impl FnOnce() -> T) -> T {
+    let previous = DEADLINE.with(|d| d.replace(deadline));
+    let result = f();
+    DEADLINE.with(|d| d.set(previous));
+    result
+}
+
+/// Deadline set by the innermost `with_deadline` on this thread, if any
+pub fn current_deadline() -> Option<Instant> {
+    DEADLINE.with(Cell::get)
+}
+
+/// Retry policy for source fetches
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled on every further retry
+    pub initial_delay: Duration,
+    /// Upper bound on a single delay, including one requested by `Retry-After`
+    pub max_delay: Duration,
+    /// Random jitter as a fraction of the backoff delay, in [0, 1]
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    /// 3 attempts with 30s, 60s backoff (±20%)
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_secs(30),
+            max_delay: Duration::from_secs(120),
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Build policy from environment variables, falling back to defaults:
+    /// `SOURCE_RETRY_MAX_ATTEMPTS`, `SOURCE_RETRY_INITIAL_DELAY_SECS`,
+    /// `SOURCE_RETRY_MAX_DELAY_SECS`, `SOURCE_RETRY_JITTER`
+    pub fn from_env() -> Result<Self> {
+        Self::from_env_prefixed("SOURCE_RETRY", Self::default())
+    }
+
+    /// Build policy from `{prefix}_MAX_ATTEMPTS`, `{prefix}_INITIAL_DELAY_SECS`,
+    /// `{prefix}_MAX_DELAY_SECS` and `{prefix}_JITTER`, falling back to `defaults`
+    pub fn from_env_prefixed(prefix: &str, defaults: Self) -> Result<Self> {
+        let var = |name: &str| {
+            let key = format!("{prefix}_{name}");
+            std::env::var(&key).ok().map(|v| (key, v))
+        };
+        let mut policy = defaults;
+        if let Some((key, v)) = var("MAX_ATTEMPTS") {
+            policy.max_attempts = v.trim().parse().with_context(|| format!("Invalid {key}"))?;
+        }
+        if let Some((key, v)) = var("INITIAL_DELAY_SECS") {
+            policy.initial_delay =
+                Duration::from_secs(v.trim().parse().with_context(|| format!("Invalid {key}"))?);
+        }
+        if let Some((key, v)) = var("MAX_DELAY_SECS") {
+            policy.max_delay =
+                Duration::from_secs(v.trim().parse().with_context(|| format!("Invalid {key}"))?);
+        }
+        if let Some((key, v)) = var("JITTER") {
+            policy.jitter = v.trim().parse().with_context(|| format!("Invalid {key}"))?;
+        }
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.max_attempts == 0 {
+            bail!("retry policy needs at least one attempt");
+        }
+        if !(0.0..=1.0).contains(&self.jitter) {
+            bail!("retry jitter must be within [0, 1], got {}", self.jitter);
+        }
+        Ok(())
+    }
+
+    /// Backoff before retry number `retry` (1-based), before jitter
+    fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_delay
+            .saturating_mul(factor)
+            .min(self.max_delay)
+    }
+
+    /// Delay before retry number `retry`: `Retry-After` (capped at `max_delay`) if given,
+    /// else jittered backoff
+    fn delay(&self, retry: u32, error: &FetchError) -> Duration {
+        match error.retry_after() {
+            Some(retry_after) => retry_after.min(self.max_delay),
+            None => self.jittered_backoff(retry),
+        }
+    }
+
+    /// Backoff before retry number `retry` (1-based), with random jitter applied
+    pub fn jittered_backoff(&self, retry: u32) -> Duration {
+        let backoff = self.backoff(retry);
+        // Uniform in [-1, 1], from a randomly keyed hasher (no RNG dependency)
+        let unit = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+        backoff.mul_f64(1.0 + self.jitter * (2.0 * unit - 1.0))
+    }
+}
+
+/// `GET` `url`, retrying transient failures per `policy` until `deadline`
+///
+/// Permanent failures (4xx other than 408/429, invalid URLs, oversized bodies) fail
+/// immediately. Each attempt keeps the client's configured timeout, shortened to the time
+/// left before `deadline`, and no retry is scheduled that would start after it.
+pub fn fetch_with_retry(
+    client: &HttpClient,
+    url: &str,
+    policy: &RetryPolicy,
+    deadline: Option<Instant>,
+) -> Result<Vec<u8>> {
+    let remaining = || deadline.map(|d| d.saturating_duration_since(Instant::now()));
+
+    for attempt in 1..=policy.max_attempts {
+        let timeout = remaining();
+        if timeout == Some(Duration::ZERO) {
+            bail!("deadline reached before GET {url} (attempt {attempt})");
+        }
+
+        let error = match client.get_with_timeout(url, timeout) {
+            Ok(bytes) => return Ok(bytes),
+            Err(e) => e,
+        };
+        if !error.is_transient() {
+            return Err(anyhow!(error).context(format!("Permanent failure for {url}")));
+        }
+        if attempt == policy.max_attempts {
+            return Err(
+                anyhow!(error).context(format!("Failed after {} attempts", policy.max_attempts))
+            );
+        }
+
+        let delay = policy.delay(attempt, &error);
+        if remaining().is_some_and(|left| delay >= left) {
+            return Err(anyhow!(error).context(format!(
+                "Deadline leaves no time to retry {url} after attempt {attempt}"
+            )));
+        }
+        warn!(
+            "Request to {url} failed (attempt {attempt}/{}): {error}; retrying in {:.1}s...",
+            policy.max_attempts,
+            delay.as_secs_f64()
+        );
+        thread::sleep(delay);
+    }
+
+    unreachable!("retry loop always returns")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_http::{response, serve};
+    use crate::sources::http::HttpConfig;
+    use std::sync::atomic::Ordering;
+
+    fn ok() -> String {
+        response("200 OK", "", "ok")
+    }
+
+    fn unavailable() -> String {
+        response("503 Service Unavailable", "", "")
+    }
+
+    fn not_found() -> String {
+        response("404 Not Found", "", "")
+    }
+
+    fn rate_limited() -> String {
+        response("429 Too Many Requests", "Retry-After: 1\r\n", "")
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(50),
+            jitter: 0.5,
+        }
+    }
+
+    fn client() -> HttpClient {
+        HttpClient::new(&HttpConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn retries_transient_then_succeeds() {
+        let (url, count) = serve(vec![unavailable(), unavailable(), ok()]);
+        let body = fetch_with_retry(&client(), &url, &fast_policy(), None).unwrap();
+        assert_eq!(body, b"ok");
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn does_not_retry_permanent_failures() {
+        let (url, count) = serve(vec![not_found(), ok()]);
+        let err = fetch_with_retry(&client(), &url, &fast_policy(), None).unwrap_err();
+        assert!(err.to_string().contains("Permanent failure"));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let (url, count) = serve(vec![unavailable(), unavailable(), unavailable()]);
+        let err = fetch_with_retry(&client(), &url, &fast_policy(), None).unwrap_err();
+        assert!(err.to_string().contains("Failed after 3 attempts"));
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+    }
+
+    /// `fast_policy` with room for a 1s `Retry-After`
+    fn patient_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_delay: Duration::from_secs(2),
+            ..fast_policy()
+        }
+    }
+
+    #[test]
+    fn honours_retry_after() {
+        let (url, _) = serve(vec![rate_limited(), ok()]);
+        let start = Instant::now();
+        fetch_with_retry(&client(), &url, &patient_policy(), None).unwrap();
+        assert!(start.elapsed() >= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn caps_retry_after_at_max_delay() {
+        let error = FetchError::Status {
+            url: "http://example.com".to_string(),
+            status: reqwest::StatusCode::TOO_MANY_REQUESTS,
+            retry_after: Some(Duration::from_secs(86_400)),
+        };
+        assert_eq!(
+            RetryPolicy::default().delay(1, &error),
+            Duration::from_secs(120)
+        );
+
+        // Without a deadline, a day-long Retry-After still only waits max_delay
+        let (url, count) = serve(vec![
+            response("503 Service Unavailable", "Retry-After: 86400\r\n", ""),
+            ok(),
+        ]);
+        let start = Instant::now();
+        fetch_with_retry(&client(), &url, &fast_policy(), None).unwrap();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn respects_deadline() {
+        // Retry-After of 1s cannot fit in a 200ms deadline
+        let (url, count) = serve(vec![rate_limited(), ok()]);
+        let deadline = Instant::now() + Duration::from_millis(200);
+        let err = fetch_with_retry(&client(), &url, &patient_policy(), Some(deadline)).unwrap_err();
+        assert!(err.to_string().contains("Deadline"));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        // Expired deadline never sends a request
+        let err =
+            fetch_with_retry(&client(), &url, &fast_policy(), Some(Instant::now())).unwrap_err();
+        assert!(err.to_string().contains("deadline reached"));
+    }
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(1), Duration::from_secs(30));
+        assert_eq!(policy.backoff(2), Duration::from_secs(60));
+        assert_eq!(policy.backoff(3), Duration::from_secs(120));
+        assert_eq!(policy.backoff(10), Duration::from_secs(120));
+    }
+
+    #[test]
+    fn jitter_stays_in_bounds() {
+        let policy = RetryPolicy::default();
+        let error = FetchError::Timeout("timed out".to_string());
+        for _ in 0..100 {
+            let delay = policy.delay(1, &error);
+            assert!(delay >= Duration::from_secs(24) && delay <= Duration::from_secs(36));
+        }
+    }
+
+    #[test]
+    fn deadline_is_scoped() {
+        assert_eq!(current_deadline(), None);
+        let deadline = Instant::now() + Duration::from_secs(60);
+        with_deadline(Some(deadline), || {
+            assert_eq!(current_deadline(), Some(deadline));
+            with_deadline(None, || assert_eq!(current_deadline(), None));
+            assert_eq!(current_deadline(), Some(deadline));
+        });
+        assert_eq!(current_deadline(), None);
+    }
+}
@@ -1,20 +1,77 @@
-use alloy::signers::local::PrivateKeySigner;
+use alloy::{primitives::Address, signers::local::PrivateKeySigner};
 use anyhow::{Context, Result};
-use chrono::{NaiveDate, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use log::{error, info, warn};
 use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
 use tokio::time::Duration;
 
-use super::{chain::HyperliquidClient, get_aqa_ref_rate};
+use super::{
+    chain::{
+        HyperliquidClient, Network, NonceManager, VoteConfirmation,
+        api::DEFAULT_MAX_CLOCK_SKEW,
+        confirmation::confirm_vote,
+        keystore::load_keystores_from_env,
+        nonce::NONCE_MAX_AGE,
+        offline::{DryRunRequest, SignedRequest, parse_signed_request},
+        pkcs11::{Pkcs11Config, open_pkcs11_signers},
+        preflight::{PreflightMode, check_signers, resolve_validators},
+        signer::{RemoteEndpoint, RemoteSigner, VoteSigner, signer_name},
+        signer_config::{SIGNERS_FILE_ENV, SignerConfig, reject_duplicates},
+        submit::{log_outcomes, submit_all, vote_retry_policy_from_env},
+        types::ValidatorL1StreamAction,
+    },
+    formula::FormulaSet,
+    get_aqa_ref_rate,
+    sources::retry::with_deadline,
+};
+
+/// Scaled units per 1.0 (100%) in decimal submission format
+const SUBMISSION_SCALE: u64 = 100_000_000;
 
 /// Convert scaled rate (where 1% = 1,000,000) to decimal string format (e.g., "0.045" for 4.5%)
 /// Dev: (1) divide by 1MM to get percentage, (2) divide by 100 to get decimal, (3) return 8 decimals
 ///      4,500,000 -> 4.5% -> 0.045
+///
+/// Formatting is exact integer arithmetic: the scaled rate already carries exactly 8 decimal
+/// places of the submitted value, so the integer and fractional parts are printed directly and
+/// no rounding ever happens here. All rounding on the path to the vote happens earlier and is
+/// flooring (see `get_aqa_ref_rate`).
 pub fn fmt_scaled_rate(scaled_rate: u64) -> String {
-    format!("{:.8}", scaled_rate as f64 / 100_000_000.0)
+    format!(
+        "{}.{:08}",
+        scaled_rate / SUBMISSION_SCALE,
+        scaled_rate % SUBMISSION_SCALE
+    )
+}
+
+/// Parse a decimal submission string (e.g., "0.04500000") back into a scaled rate
+/// Inverse of `fmt_scaled_rate`; rejects signs, exponents and more than 8 decimal places
+pub fn parse_scaled_rate(rate: &str) -> Result<u64> {
+    let (int_part, frac_part) = rate.split_once('.').unwrap_or((rate, ""));
+    let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if int_part.is_empty() || !is_digits(int_part) || !is_digits(frac_part) {
+        anyhow::bail!("invalid rate '{rate}'");
+    }
+    if frac_part.len() > 8 {
+        anyhow::bail!("rate '{rate}' has more than 8 decimal places");
+    }
+
+    // Right-pad fractional digits to exactly 8 places
+    let frac: u64 = format!("{frac_part:0<8}").parse()?;
+    int_part
+        .parse::<u64>()
+        .ok()
+        .and_then(|int| int.checked_mul(SUBMISSION_SCALE))
+        .and_then(|int| int.checked_add(frac))
+        .with_context(|| format!("rate '{rate}' out of range"))
 }
 
 /// Adjusts a scaled rate from an ACT/360 basis to an ACT/365.25 basis
+/// Equivalent to the basis conversion of the default (`v1`) `RateFormula`
 ///
 /// SOFR is published on an ACT/360 basis (simple interest over current
 /// period, simplifying scaling). However, AQA rate publishing expects
@@ -34,7 +91,7 @@ pub fn fmt_duration(duration: Duration) -> String {
     let hours = total_seconds / 3600;
     let minutes = (total_seconds % 3600) / 60;
     let seconds = total_seconds % 60;
-    format!("{}h {}m {}s", hours, minutes, seconds)
+    format!("{hours}h {minutes}m {seconds}s")
 }
 
 /// Calculate duration until next scheduled execution at `target_hour` UTC
@@ -48,45 +105,261 @@ pub fn duration_until_next_execution(target_hour: u32) -> Duration {
 
     // If we've already passed today's target time, schedule for tomorrow
     if next_run <= now {
-        next_run = next_run + chrono::Duration::days(1);
+        next_run += chrono::Duration::days(1);
+    }
+
+    (next_run - now).to_std().unwrap()
+}
+
+/// Validity window of signed votes, from `VOTE_EXPIRES_AFTER_SECS` (unset: no expiry)
+fn vote_expires_in() -> Result<Option<Duration>> {
+    env::var("VOTE_EXPIRES_AFTER_SECS")
+        .ok()
+        .map(|secs| {
+            secs.trim()
+                .parse()
+                .map(Duration::from_secs)
+                .context("Invalid VOTE_EXPIRES_AFTER_SECS")
+        })
+        .transpose()
+}
+
+/// Largest tolerated clock skew against the exchange, from `MAX_CLOCK_SKEW_SECS`
+/// (default 30s; 0 disables the check)
+fn max_clock_skew() -> Result<Option<Duration>> {
+    match env::var("MAX_CLOCK_SKEW_SECS") {
+        Ok(secs) => {
+            let secs: u64 = secs.trim().parse().context("Invalid MAX_CLOCK_SKEW_SECS")?;
+            Ok((secs > 0).then(|| Duration::from_secs(secs)))
+        }
+        Err(_) => Ok(Some(DEFAULT_MAX_CLOCK_SKEW)),
+    }
+}
+
+/// Delay before confirming submitted votes, from `VOTE_CONFIRM_DELAY_SECS` (default 5s)
+fn vote_confirm_delay() -> Result<Duration> {
+    match env::var("VOTE_CONFIRM_DELAY_SECS") {
+        Ok(secs) => Ok(Duration::from_secs(
+            secs.trim()
+                .parse()
+                .context("Invalid VOTE_CONFIRM_DELAY_SECS")?,
+        )),
+        Err(_) => Ok(Duration::from_secs(5)),
+    }
+}
+
+/// Whether `fetch_and_publish_aqa` fails when an accepted vote is not confirmed, from
+/// `VOTE_CONFIRM_REQUIRED` (`true` or `false`, default `false`)
+fn vote_confirm_required() -> Result<bool> {
+    match env::var("VOTE_CONFIRM_REQUIRED") {
+        Ok(required) => required
+            .trim()
+            .parse()
+            .context("Invalid VOTE_CONFIRM_REQUIRED (expected `true` or `false`)"),
+        Err(_) => Ok(false),
+    }
+}
+
+/// Query the info API once for the current votes and report a `VoteConfirmation` per
+/// accepted signer, checked against the vote of the validator it signs for (its configured
+/// validator, else the one it is linked to as validator, signer key or agent)
+/// Mismatched or missing votes are logged as errors (for alerting) but do not fail the run
+pub async fn confirm_votes(
+    client: &HyperliquidClient,
+    signers: &[Arc<dyn VoteSigner>],
+    rate: &str,
+) -> Result<Vec<(Address, Option<VoteConfirmation>)>> {
+    if signers.is_empty() {
+        return Ok(Vec::new());
+    }
+    tokio::time::sleep(vote_confirm_delay()?).await;
+
+    let lookup = async {
+        let validators = resolve_validators(client, signers).await?;
+        let entries = client.validator_l1_votes().await?;
+        anyhow::Ok((validators, entries))
+    };
+    let (validators, entries) = match lookup.await {
+        Ok(lookup) => lookup,
+        Err(e) => {
+            error!("Failed to confirm votes: {e}");
+            return Ok(signers.iter().map(|s| (s.address(), None)).collect());
+        }
+    };
+
+    let mut confirmations = Vec::with_capacity(signers.len());
+    for (signer, validator) in signers.iter().zip(validators) {
+        let name = signer_name(signer.as_ref());
+        let confirmation = match validator {
+            Some(validator) => {
+                let confirmation = confirm_vote(&entries, validator, rate);
+                if confirmation.is_confirmed() {
+                    info!(
+                        "Vote confirmation for signer {name} (validator {validator}): {confirmation}"
+                    );
+                } else {
+                    error!(
+                        "Vote confirmation for signer {name} (validator {validator}): {confirmation}"
+                    );
+                }
+                Some(confirmation)
+            }
+            None => {
+                error!("Failed to confirm vote for signer {name}: not linked to any validator");
+                None
+            }
+        };
+        confirmations.push((signer.address(), confirmation));
+    }
+
+    let confirmed = confirmations
+        .iter()
+        .filter(|(_, c)| c.as_ref().is_some_and(VoteConfirmation::is_confirmed))
+        .count();
+    info!(
+        "Vote confirmation complete: {confirmed}/{} confirmed",
+        confirmations.len()
+    );
+    Ok(confirmations)
+}
+
+/// Time budget for a single publishing run, from `RUN_DEADLINE_SECS` (default 1 hour)
+/// Dev: a run that has not finished within its budget gives up rather than voting late
+pub fn run_budget() -> Result<Duration> {
+    match env::var("RUN_DEADLINE_SECS") {
+        Ok(secs) => Ok(Duration::from_secs(
+            secs.trim().parse().context("Invalid RUN_DEADLINE_SECS")?,
+        )),
+        Err(_) => Ok(Duration::from_secs(3600)),
     }
+}
 
-    let duration = (next_run - now).to_std().unwrap();
-    duration
+/// Load signers for `network` from the signer configuration file in
+/// `PUBLISHER_SIGNERS_FILE` or, if not set, from environment: local keys from
+/// `PUBLISHER_PRIVATE_KEY`, encrypted keystores from `PUBLISHER_KEYSTORE`, PKCS#11 keys from
+/// `PKCS11_KEY_LABELS` and/or remote signers for `REMOTE_SIGNER_ADDRESSES` at
+/// `REMOTE_SIGNER_URL` (at least one required). Signers sharing an address are rejected
+/// Dev: call once at startup; a keystore password descriptor can only be read once
+pub fn load_signers(network: &Network) -> Result<Vec<Arc<dyn VoteSigner>>> {
+    let signers = match SignerConfig::from_env()? {
+        Some(config) => {
+            if let Some(var) = SIGNER_ENV_VARS.iter().find(|var| env::var(var).is_ok()) {
+                anyhow::bail!("{var} cannot be combined with {SIGNERS_FILE_ENV}");
+            }
+            config.load(network)?
+        }
+        None => signers_from_env()?,
+    };
+    reject_duplicates(&signers)?;
+    for signer in &signers {
+        match signer.validator() {
+            Some(validator) => info!(
+                "Loaded signer {} for validator {validator}",
+                signer_name(signer.as_ref())
+            ),
+            None => info!("Loaded signer {}", signer_name(signer.as_ref())),
+        }
+    }
+    Ok(signers)
 }
 
-/// Parse private keys from environment variable, `PUBLISHER_PRIVATE_KEY`
-fn load_signers() -> Result<Vec<PrivateKeySigner>> {
-    // Read environment variable
-    let private_keys_str = env::var("PUBLISHER_PRIVATE_KEY")
-        .context("PUBLISHER_PRIVATE_KEY environment variable must be set")?;
+/// Signer variables replaced by a signer configuration file
+const SIGNER_ENV_VARS: [&str; 4] = [
+    "PUBLISHER_PRIVATE_KEY",
+    "PUBLISHER_KEYSTORE",
+    "PKCS11_MODULE",
+    "REMOTE_SIGNER_URL",
+];
+
+/// Load signers from the `PUBLISHER_PRIVATE_KEY`, `PUBLISHER_KEYSTORE`, `PKCS11_*` and
+/// `REMOTE_SIGNER_*` variables
+fn signers_from_env() -> Result<Vec<Arc<dyn VoteSigner>>> {
+    let mut loaded: Vec<Arc<dyn VoteSigner>> = Vec::new();
+    for signer in load_keystores_from_env()? {
+        loaded.push(Arc::new(signer));
+    }
+    if let Some(config) = Pkcs11Config::from_env()? {
+        for signer in open_pkcs11_signers(&config)? {
+            loaded.push(Arc::new(signer));
+        }
+    }
+
+    parse_signers(
+        env::var("PUBLISHER_PRIVATE_KEY").ok().as_deref(),
+        loaded,
+        env::var("REMOTE_SIGNER_URL").ok().as_deref(),
+        env::var("REMOTE_SIGNER_ADDRESSES").ok().as_deref(),
+    )
+}
 
-    // Split by comma to support multiple keys
-    let key_strings: Vec<&str> = private_keys_str.split(',').map(|s| s.trim()).collect();
+/// Build signers from comma-separated private keys, already loaded (keystore and PKCS#11)
+/// signers and a remote signer endpoint with its comma-separated addresses
+fn parse_signers(
+    private_keys: Option<&str>,
+    loaded: Vec<Arc<dyn VoteSigner>>,
+    remote_url: Option<&str>,
+    remote_addresses: Option<&str>,
+) -> Result<Vec<Arc<dyn VoteSigner>>> {
+    let split = |list: &str| -> Vec<String> {
+        list.split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    };
+    let mut signers: Vec<Arc<dyn VoteSigner>> = Vec::new();
 
-    // Parse keys as signers
-    let mut signers = Vec::new();
-    for (idx, key_str) in key_strings.iter().enumerate() {
+    // Parse keys as local signers
+    for (idx, key_str) in private_keys
+        .map(split)
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+    {
         let signer: PrivateKeySigner = key_str
             .parse()
-            .context(format!("Failed to parse private key at index {}", idx))?;
-        signers.push(signer);
+            .context(format!("Failed to parse private key at index {idx}"))?;
+        signers.push(Arc::new(signer));
+    }
+
+    // Add keystore and PKCS#11 signers
+    signers.extend(loaded);
+
+    // Add remote signers, one per address held by the signing service
+    match (remote_url, remote_addresses) {
+        (Some(url), Some(addresses)) => {
+            let endpoint = RemoteEndpoint::parse(url)?;
+            for (idx, address) in split(addresses).iter().enumerate() {
+                let address: Address = address.parse().context(format!(
+                    "Failed to parse remote signer address at index {idx}"
+                ))?;
+                signers.push(Arc::new(RemoteSigner::new(address, endpoint.clone())));
+            }
+        }
+        (Some(_), None) => anyhow::bail!("REMOTE_SIGNER_URL requires REMOTE_SIGNER_ADDRESSES"),
+        (None, Some(_)) => anyhow::bail!("REMOTE_SIGNER_ADDRESSES requires REMOTE_SIGNER_URL"),
+        (None, None) => {}
     }
 
-    // Check for at least 1 publishing key
+    // Check for at least 1 publishing signer
     if signers.is_empty() {
-        anyhow::bail!("No valid private keys found in PUBLISHER_PRIVATE_KEY");
+        anyhow::bail!(
+            "No signers configured; set PUBLISHER_PRIVATE_KEY, PUBLISHER_KEYSTORE, PKCS11_MODULE and/or REMOTE_SIGNER_URL with REMOTE_SIGNER_ADDRESSES"
+        );
     }
 
     Ok(signers)
 }
 
 /// Fetch AQA rate data without publishing
-pub async fn fetch_aqa() -> Result<(NaiveDate, u64, u64)> {
+/// Source fetches (including retries) give up once `deadline` is reached
+pub async fn fetch_aqa(deadline: Option<Instant>) -> Result<(NaiveDate, u64, u64)> {
     // Run blocking HTTP calls in a separate thread pool to avoid blocking the async runtime
-    let (median_date, raw_sofr_avg, aqa_ref_rate) = tokio::task::spawn_blocking(|| {
-        let date = Utc::now().date_naive();
-        get_aqa_ref_rate(date)
+    let (median_date, raw_sofr_avg, aqa_ref_rate) = tokio::task::spawn_blocking(move || {
+        with_deadline(deadline, || {
+            let formulas = FormulaSet::from_env()?;
+            let date = Utc::now().date_naive();
+            get_aqa_ref_rate(date, &formulas)
+        })
     })
     .await
     .context("Failed to spawn blocking task")?
@@ -95,86 +368,265 @@ pub async fn fetch_aqa() -> Result<(NaiveDate, u64, u64)> {
     Ok((median_date, raw_sofr_avg, aqa_ref_rate))
 }
 
-/// Fetch and publish AQA rate via validator vote
-pub async fn fetch_and_publish_aqa() -> Result<()> {
+/// Client for `network` configured from environment (`NONCE_FILE`, `MAX_CLOCK_SKEW_SECS`,
+/// `VOTE_EXPIRES_AFTER_SECS`)
+fn client_from_env(network: &Network) -> Result<HyperliquidClient> {
+    let mut client = HyperliquidClient::new(network.clone())
+        .with_nonce_manager(Arc::new(NonceManager::from_env()?))
+        .with_max_clock_skew(max_clock_skew()?);
+    if let Some(expires_in) = vote_expires_in()? {
+        client = client.with_expires_in(expires_in);
+    }
+    Ok(client)
+}
+
+/// Check that each of `signers` is authorized for an active validator on `network`,
+/// following `PREFLIGHT` (`enforce` refuses to start, `warn` only logs, `off` skips)
+/// An info API failure counts as a failed check
+pub async fn run_preflight(network: &Network, signers: &[Arc<dyn VoteSigner>]) -> Result<()> {
+    let client = HyperliquidClient::new(network.clone());
+    preflight(&client, signers, PreflightMode::from_env()?).await
+}
+
+/// `run_preflight` against `client` in `mode`
+async fn preflight(
+    client: &HyperliquidClient,
+    signers: &[Arc<dyn VoteSigner>],
+    mode: PreflightMode,
+) -> Result<()> {
+    if mode == PreflightMode::Off {
+        info!("Pre-flight check disabled");
+        return Ok(());
+    }
+
+    let statuses = match (check_signers(client, signers).await, mode) {
+        (Ok(statuses), _) => statuses,
+        (Err(e), PreflightMode::Enforce) => {
+            return Err(e.context(
+                "Pre-flight check could not query the info API; set PREFLIGHT=warn to start anyway",
+            ));
+        }
+        (Err(e), _) => {
+            warn!("Pre-flight check could not query the info API: {e}; continuing");
+            return Ok(());
+        }
+    };
+    let mut failure_count = 0;
+    for (name, status) in &statuses {
+        if status.is_authorized() {
+            info!("Pre-flight: signer {name} {status}");
+        } else {
+            error!("Pre-flight: signer {name} {status}");
+            failure_count += 1;
+        }
+    }
+
+    match (failure_count, mode) {
+        (0, _) => Ok(()),
+        (_, PreflightMode::Enforce) => anyhow::bail!(
+            "{failure_count} signer(s) failed the pre-flight check; set PREFLIGHT=warn to start anyway"
+        ),
+        _ => {
+            warn!("{failure_count} signer(s) failed the pre-flight check; continuing");
+            Ok(())
+        }
+    }
+}
+
+/// Fetch and publish AQA rate via validator vote from each of `signers` on `network`,
+/// returning the confirmation of each accepted vote (see `confirm_votes`)
+/// Source fetches (including retries) give up once `deadline` is reached. With
+/// `VOTE_CONFIRM_REQUIRED=true`, any mismatched or unconfirmed vote fails the run
+pub async fn fetch_and_publish_aqa(
+    network: &Network,
+    signers: &[Arc<dyn VoteSigner>],
+    deadline: Option<Instant>,
+) -> Result<Vec<(Address, Option<VoteConfirmation>)>> {
+    let confirm_required = vote_confirm_required()?;
+
     // Get AQA reference rate
-    let (median_date, _, aqa_ref_rate) = fetch_aqa().await?;
-    info!("AQA rate on {}: {}", median_date, aqa_ref_rate);
+    let (median_date, _, aqa_ref_rate) = fetch_aqa(deadline).await?;
+    info!("AQA rate on {median_date}: {aqa_ref_rate}");
 
     // Convert to decimal string format for API payload
     let rfr_rate = fmt_scaled_rate(aqa_ref_rate);
-    info!("Submission-formatted rate: {}", rfr_rate);
+    info!("Submission-formatted rate: {rfr_rate}");
 
-    // Load signers from environment
-    let signers = load_signers()?;
-    info!("Loaded {} publishing signer(s)", signers.len());
+    info!("Publishing with {} signer(s)", signers.len());
 
-    // Determine network (default to `mainnet`)
-    let is_mainnet = match env::var("NETWORK").as_deref() {
-        Ok("testnet") => {
-            info!("Publishing to testnet");
-            false
-        }
-        _ => {
-            info!("Publishing to mainnet");
-            true
+    // Setup one client shared by all signers
+    info!("Publishing to {network}");
+    let hl_client = Arc::new(client_from_env(network)?);
+
+    // Submit all votes concurrently, retrying transient failures per signer
+    let policy = vote_retry_policy_from_env()?;
+    let outcomes = submit_all(hl_client.clone(), signers, &rfr_rate, &policy).await;
+    log_outcomes(&outcomes);
+
+    let accepted: Vec<Arc<dyn VoteSigner>> = signers
+        .iter()
+        .filter(|s| {
+            outcomes
+                .iter()
+                .any(|o| o.signer == s.address() && o.is_success())
+        })
+        .cloned()
+        .collect();
+    let success_count = accepted.len();
+    let failure_count = outcomes.len() - success_count;
+
+    // Confirm accepted votes actually landed as intended
+    let confirmations = confirm_votes(&hl_client, &accepted, &rfr_rate).await?;
+
+    // Fail if all submissions failed
+    if success_count == 0 {
+        anyhow::bail!("All validator votes failed");
+    }
+
+    // Warn if some submissions failed
+    if failure_count > 0 {
+        warn!("{failure_count} out of {} votes failed", signers.len());
+    }
+
+    // Fail on unconfirmed votes only if required
+    let unconfirmed = confirmations
+        .iter()
+        .filter(|(_, c)| !c.as_ref().is_some_and(VoteConfirmation::is_confirmed))
+        .count();
+    if unconfirmed > 0 && confirm_required {
+        anyhow::bail!(
+            "{unconfirmed} out of {success_count} accepted votes were not confirmed \
+             (VOTE_CONFIRM_REQUIRED is set)"
+        );
+    }
+
+    Ok(confirmations)
+}
+
+/// Run the publish path up to, but excluding, the `/exchange` POST: fetch the AQA rate and
+/// sign a vote with each of `signers` on `network`, returning each request that would be
+/// posted with its hashes
+/// Dev: nonces are kept in memory so a dry run never advances the persisted nonce state
+pub async fn dry_run_aqa(
+    network: &Network,
+    signers: &[Arc<dyn VoteSigner>],
+    deadline: Option<Instant>,
+) -> Result<Vec<DryRunRequest<'static>>> {
+    let (median_date, _, aqa_ref_rate) = fetch_aqa(deadline).await?;
+    info!("AQA rate on {median_date}: {aqa_ref_rate}");
+    let rfr_rate = fmt_scaled_rate(aqa_ref_rate);
+    info!("Submission-formatted rate: {rfr_rate}");
+
+    info!(
+        "Dry run: signing with {} signer(s) for {network}",
+        signers.len()
+    );
+    let client = client_from_env(network)?.with_nonce_manager(Arc::new(NonceManager::in_memory()));
+
+    let mut requests = Vec::with_capacity(signers.len());
+    for signer in signers {
+        let request = client
+            .sign_action(signer.as_ref(), ValidatorL1StreamAction::new(&rfr_rate))
+            .await
+            .with_context(|| format!("Failed to sign for {}", signer_name(signer.as_ref())))?;
+        requests.push(DryRunRequest::new(network, signer.as_ref(), request)?);
+    }
+    Ok(requests)
+}
+
+/// Sign a vote for `rate` (or, if `None`, the current AQA rate) with each of `signers`
+/// without sending it, writing each signed request to `dir` for `broadcast_signed`
+/// Dev: runs without exchange access, so the clock skew check is skipped
+pub async fn sign_votes_offline(
+    network: &Network,
+    signers: &[Arc<dyn VoteSigner>],
+    rate: Option<&str>,
+    deadline: Option<Instant>,
+    dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    // Use the given rate (normalized to 8 decimals) or compute it
+    let rfr_rate = match rate {
+        Some(rate) => fmt_scaled_rate(parse_scaled_rate(rate)?),
+        None => {
+            let (median_date, _, aqa_ref_rate) = fetch_aqa(deadline).await?;
+            info!("AQA rate on {median_date}: {aqa_ref_rate}");
+            fmt_scaled_rate(aqa_ref_rate)
         }
     };
+    info!("Submission-formatted rate: {rfr_rate}");
 
-    // Track submission results
-    let mut success_count = 0;
-    let mut failure_count = 0;
+    info!("Signing {} vote(s) for {network}", signers.len());
+    if vote_expires_in()?.is_some_and(|expires_in| expires_in >= NONCE_MAX_AGE) {
+        warn!(
+            "VOTE_EXPIRES_AFTER_SECS is beyond the exchange's {}h nonce window; signed votes \
+             are rejected once their nonce is that old",
+            NONCE_MAX_AGE.as_secs() / 3600
+        );
+    }
+    let client = client_from_env(network)?.with_max_clock_skew(None);
 
-    // Submit vote for each signer
-    for (idx, signer) in signers.iter().enumerate() {
+    let mut paths = Vec::with_capacity(signers.len());
+    for signer in signers {
+        let request = client
+            .sign_action(signer.as_ref(), ValidatorL1StreamAction::new(&rfr_rate))
+            .await?;
+        let signed = SignedRequest {
+            network: network.into(),
+            signer: signer.address(),
+            request,
+        };
+        let path = signed.write_to(dir)?;
+        let nonce_limit = signed.request.nonce + NONCE_MAX_AGE.as_millis() as u64;
+        let broadcast_by = signed
+            .request
+            .expires_after
+            .map_or(nonce_limit, |expires_after| expires_after.min(nonce_limit));
         info!(
-            "Submitting vote {}/{} with signer: {}",
-            idx + 1,
-            signers.len(),
-            signer.address()
+            "Signed vote for signer {} -> {} (broadcast before {})",
+            signer_name(signer.as_ref()),
+            path.display(),
+            DateTime::from_timestamp_millis(broadcast_by as i64)
+                .map_or(broadcast_by.to_string(), |t| t.to_rfc3339())
         );
+        paths.push(path);
+    }
+    Ok(paths)
+}
 
-        // Setup client
-        let hl_client = HyperliquidClient::new(signer.clone(), is_mainnet);
-
-        // Submit vote and handle response
-        let response = hl_client.submit_vote(&rfr_rate).await;
-        match response {
-            Ok(resp) => {
-                info!(
-                    "Validator vote success for signer {}: {:?}",
-                    signer.address(),
-                    resp
-                );
-                success_count += 1;
-            }
+/// Validate each signed request file against `network` and post it to the exchange
+/// Fails if any request is invalid or rejected; all files are attempted first
+pub async fn broadcast_signed(network: &Network, paths: &[PathBuf]) -> Result<()> {
+    let client = HyperliquidClient::new(network.clone());
+    let mut failure_count = 0;
+    for path in paths {
+        let result = async {
+            let contents = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let signed = parse_signed_request::<ValidatorL1StreamAction>(&contents)?;
+            signed.validate(network, Utc::now().timestamp_millis() as u64)?;
+            let response = client.broadcast(&signed.request).await?;
+            anyhow::Ok((signed.signer, response))
+        }
+        .await;
+        match result {
+            Ok((signer, response)) => info!(
+                "Broadcast {} for signer {signer}: {response:?}",
+                path.display()
+            ),
             Err(e) => {
-                error!(
-                    "Failed to submit vote for signer {}: {}",
-                    signer.address(),
-                    e
-                );
+                error!("Failed to broadcast {}: {e}", path.display());
                 failure_count += 1;
             }
         }
     }
 
-    // Report summary
     info!(
-        "Vote submission complete: {} succeeded, {} failed",
-        success_count, failure_count
+        "Broadcast complete: {} succeeded, {failure_count} failed",
+        paths.len() - failure_count
     );
-
-    // Fail if all submissions failed
-    if success_count == 0 {
-        anyhow::bail!("All validator votes failed");
-    }
-
-    // Warn if some submissions failed
     if failure_count > 0 {
-        warn!("{} out of {} votes failed", failure_count, signers.len());
+        anyhow::bail!("{failure_count} out of {} broadcasts failed", paths.len());
     }
-
     Ok(())
 }
 
@@ -193,6 +645,58 @@ mod tests {
             assert_eq!(fmt_scaled_rate(100_000_000), "1.00000000");
             assert_eq!(fmt_scaled_rate(12_345_678), "0.12345678");
         }
+
+        #[test]
+        fn no_float_rounding() {
+            // Values where `as f64 / 1e8` formatting is not guaranteed to be exact
+            assert_eq!(fmt_scaled_rate(1), "0.00000001");
+            assert_eq!(fmt_scaled_rate(3_515_319), "0.03515319");
+            assert_eq!(fmt_scaled_rate(123_456_789_012), "1234.56789012");
+            assert_eq!(fmt_scaled_rate(u64::MAX), "184467440737.09551615");
+        }
+
+        #[test]
+        fn round_trips_full_plausible_range() {
+            use rayon::prelude::*;
+
+            // Every scaled rate from 0% to 15% (upper plausible bound) must format to
+            // exactly 8 decimals and parse back to the same scaled integer
+            (0..=15_000_000u64).into_par_iter().for_each(|scaled| {
+                let formatted = fmt_scaled_rate(scaled);
+                assert_eq!(formatted.len(), 10, "{formatted}");
+                assert_eq!(parse_scaled_rate(&formatted).unwrap(), scaled);
+            });
+        }
+
+        #[test]
+        fn round_trips_extremes() {
+            for scaled in [u64::MAX, u64::MAX - 1, u64::MAX / 2, 10u64.pow(18)] {
+                assert_eq!(parse_scaled_rate(&fmt_scaled_rate(scaled)).unwrap(), scaled);
+            }
+        }
+    }
+
+    mod parse_scaled_rate_tests {
+        use super::*;
+
+        #[test]
+        fn parses_submission_format() {
+            assert_eq!(parse_scaled_rate("0.04500000").unwrap(), 4_500_000);
+            assert_eq!(parse_scaled_rate("0.045").unwrap(), 4_500_000);
+            assert_eq!(parse_scaled_rate("1").unwrap(), 100_000_000);
+        }
+
+        #[test]
+        fn rejects_invalid() {
+            assert!(parse_scaled_rate("").is_err());
+            assert!(parse_scaled_rate("abc").is_err());
+            assert!(parse_scaled_rate("-0.01").is_err());
+            assert!(parse_scaled_rate("0.000000001").is_err());
+            assert!(parse_scaled_rate(".5").is_err());
+            assert!(parse_scaled_rate("+0.5").is_err());
+            assert!(parse_scaled_rate("4.2e0").is_err());
+            assert!(parse_scaled_rate("184467440738").is_err());
+        }
     }
 
     mod adjust_basis_tests {
@@ -205,4 +709,83 @@ mod tests {
             assert_eq!(adjust_basis(5_000_000), 5_072_916);
         }
     }
+
+    mod preflight_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn unreachable_info_api_fails_only_when_enforced() {
+            let client =
+                HyperliquidClient::new(Network::custom("http://127.0.0.1:1", "b", 1337).unwrap());
+            let signers: Vec<Arc<dyn VoteSigner>> = vec![Arc::new(PrivateKeySigner::random())];
+
+            let err = preflight(&client, &signers, PreflightMode::Enforce)
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("PREFLIGHT=warn"), "{err}");
+            preflight(&client, &signers, PreflightMode::Warn)
+                .await
+                .unwrap();
+            preflight(&client, &signers, PreflightMode::Off)
+                .await
+                .unwrap();
+        }
+    }
+
+    mod parse_signers_tests {
+        use super::*;
+
+        const KEY_A: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+        const KEY_B: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+        const REMOTE: &str = "0x1111111111111111111111111111111111111111";
+
+        #[test]
+        fn local_and_remote_signers() {
+            let keys = format!("{KEY_A}, {KEY_B}");
+            let signers = parse_signers(
+                Some(&keys),
+                Vec::new(),
+                Some("unix:/run/signer.sock"),
+                Some(REMOTE),
+            )
+            .unwrap();
+            let addresses: Vec<String> = signers.iter().map(|s| s.address().to_string()).collect();
+            assert_eq!(
+                addresses,
+                [
+                    "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266",
+                    "0x70997970C51812dc3A010C7d01b50e0d17dc79C8",
+                    "0x1111111111111111111111111111111111111111",
+                ]
+            );
+
+            // Remote only
+            let signers = parse_signers(
+                None,
+                Vec::new(),
+                Some("http://127.0.0.1:9000"),
+                Some(REMOTE),
+            )
+            .unwrap();
+            assert_eq!(signers.len(), 1);
+        }
+
+        #[test]
+        fn rejects_incomplete_config() {
+            assert!(parse_signers(None, Vec::new(), None, None).is_err());
+            assert!(parse_signers(Some(""), Vec::new(), None, None).is_err());
+            assert!(parse_signers(Some("0x12"), Vec::new(), None, None).is_err());
+            assert!(parse_signers(None, Vec::new(), Some("http://127.0.0.1:9000"), None).is_err());
+            assert!(parse_signers(None, Vec::new(), None, Some(REMOTE)).is_err());
+            assert!(
+                parse_signers(
+                    None,
+                    Vec::new(),
+                    Some("http://127.0.0.1:9000"),
+                    Some("0x12")
+                )
+                .is_err()
+            );
+        }
+    }
 }
@@ -1,13 +1,16 @@
 use aqa_publisher::sources::{self, Source, fred::Fred, nyfed::NYFed, ofr::OFR};
-use chrono::{Days, Local};
+use chrono::Days;
 use rayon::prelude::*;
 use std::sync::Mutex;
 use std::thread;
 use std::time::Duration;
 
+mod common;
+
 #[test]
 fn verify_computed_averages() {
-    let end_date = Local::now().date_naive();
+    common::use_cassette("average_computation");
+    let end_date = common::end_date();
     // Test over 30 days
     let start_date = end_date.checked_sub_days(Days::new(30)).unwrap();
 
@@ -47,14 +50,13 @@ fn verify_computed_averages() {
                 Ok(diff) => {
                     if diff > MAX_DIFF {
                         date_discrepancies.push(format!(
-                            "NYFed difference {} exceeds threshold {}",
-                            diff, MAX_DIFF
+                            "NYFed difference {diff} exceeds threshold {MAX_DIFF}"
                         ));
                         success = false;
                     }
                 }
                 Err(e) => {
-                    date_errors.push(format!("NYFed: {}", e));
+                    date_errors.push(format!("NYFed: {e}"));
                     success = false;
                 }
             }
@@ -64,14 +66,13 @@ fn verify_computed_averages() {
                 Ok(diff) => {
                     if diff > MAX_DIFF {
                         date_discrepancies.push(format!(
-                            "FRED difference {} exceeds threshold {}",
-                            diff, MAX_DIFF
+                            "FRED difference {diff} exceeds threshold {MAX_DIFF}"
                         ));
                         success = false;
                     }
                 }
                 Err(e) => {
-                    date_errors.push(format!("FRED: {}", e));
+                    date_errors.push(format!("FRED: {e}"));
                     success = false;
                 }
             }
@@ -82,14 +83,13 @@ fn verify_computed_averages() {
                     Ok(diff) => {
                         if diff > MAX_DIFF {
                             date_discrepancies.push(format!(
-                                "OFR difference {} exceeds threshold {}",
-                                diff, MAX_DIFF
+                                "OFR difference {diff} exceeds threshold {MAX_DIFF}"
                             ));
                             success = false;
                         }
                     }
                     Err(e) => {
-                        date_errors.push(format!("OFR: {}", e));
+                        date_errors.push(format!("OFR: {e}"));
                         success = false;
                     }
                 }
@@ -100,14 +100,13 @@ fn verify_computed_averages() {
                 errors
                     .lock()
                     .unwrap()
-                    .push(format!("Date {}: {}", date, date_errors.join("; ")));
+                    .push(format!("Date {date}: {}", date_errors.join("; ")));
             }
             if !date_discrepancies.is_empty() {
-                discrepancies.lock().unwrap().push(format!(
-                    "Date {}: {}",
-                    date,
-                    date_discrepancies.join("; ")
-                ));
+                discrepancies
+                    .lock()
+                    .unwrap()
+                    .push(format!("Date {date}: {}", date_discrepancies.join("; ")));
             }
             if success {
                 *success_count.lock().unwrap() += 1;
@@ -125,15 +124,14 @@ fn verify_computed_averages() {
 
     // Report results
     println!(
-        "Verification complete: {}/{} dates passed all checks",
-        success_count,
+        "Verification complete: {success_count}/{} dates passed all checks",
         dates.len()
     );
 
     if !errors.is_empty() {
         println!("\n{} errors found:", errors.len());
         for (i, error) in errors.iter().take(10).enumerate() {
-            println!("  {}. {}", i + 1, error);
+            println!("  {}. {error}", i + 1);
         }
         if errors.len() > 10 {
             println!("  ... and {} more errors", errors.len() - 10);
@@ -143,7 +141,7 @@ fn verify_computed_averages() {
     if !discrepancies.is_empty() {
         println!("\n{} discrepancies found:", discrepancies.len());
         for (i, disc) in discrepancies.iter().take(10).enumerate() {
-            println!("  {}. {}", i + 1, disc);
+            println!("  {}. {disc}", i + 1);
         }
         if discrepancies.len() > 10 {
             println!("  ... and {} more discrepancies", discrepancies.len() - 10);
@@ -159,14 +157,13 @@ fn verify_computed_averages() {
 
     assert!(
         discrepancies.is_empty(),
-        "Found {} discrepancies exceeding threshold {} (see output above)",
-        discrepancies.len(),
-        MAX_DIFF
+        "Found {} discrepancies exceeding threshold {MAX_DIFF} (see output above)",
+        discrepancies.len()
     );
 }
 
 fn test_nyfed(date: chrono::NaiveDate, _max_diff: u64) -> anyhow::Result<u64> {
-    let source = NYFed::default();
+    let source = NYFed;
     let (api_date, api_avg) = source.collect(date)?;
     let overnight_rates = NYFed::fetch_overnight_rates(date)?;
     let computed = sources::compute_compounded_average(api_date, &overnight_rates)?;
@@ -174,7 +171,7 @@ fn test_nyfed(date: chrono::NaiveDate, _max_diff: u64) -> anyhow::Result<u64> {
 }
 
 fn test_fred(date: chrono::NaiveDate, _max_diff: u64) -> anyhow::Result<u64> {
-    let source = Fred::default();
+    let source = Fred;
     let (api_date, api_avg) = source.collect(date)?;
     let overnight_rates = Fred::fetch_overnight_rates(date)?;
     let computed = sources::compute_compounded_average(api_date, &overnight_rates)?;
@@ -183,16 +180,16 @@ fn test_fred(date: chrono::NaiveDate, _max_diff: u64) -> anyhow::Result<u64> {
 
 fn test_ofr(date: chrono::NaiveDate, _max_diff: u64) -> anyhow::Result<u64> {
     // Compare OFR's computed value against NY Fed's API value (ground truth)
-    let ofr_source = OFR::default();
+    let ofr_source = OFR;
     let (ofr_date, ofr_avg) = ofr_source.collect(date)?;
 
     // Get NY Fed's API-provided average for the same date
-    let nyfed_source = NYFed::default();
+    let nyfed_source = NYFed;
     let (nyfed_date, nyfed_api_avg) = nyfed_source.collect(date)?;
 
     // Ensure we're comparing the same date
     if ofr_date != nyfed_date {
-        anyhow::bail!("Date mismatch: OFR {} vs NYFed {}", ofr_date, nyfed_date);
+        anyhow::bail!("Date mismatch: OFR {ofr_date} vs NYFed {nyfed_date}");
     }
 
     Ok((ofr_avg as i64 - nyfed_api_avg as i64).unsigned_abs())
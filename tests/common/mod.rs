@@ -0,0 +1,29 @@
+use aqa_publisher::sources::cassette::{self, Cassette, CassetteMode};
+use chrono::NaiveDate;
+use std::path::PathBuf;
+
+/// Fixed end of the tested date range, so every request is covered by the cassettes
+pub fn end_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 9, 30).unwrap()
+}
+
+/// Install the cassette in `tests/cassettes/<name>` for this test binary
+///
+/// Replays by default; set `SOURCE_CASSETTE_MODE=record` to capture fresh responses or
+/// `off` to run live. Panics when replaying and nothing has been recorded.
+pub fn use_cassette(name: &str) {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/cassettes")
+        .join(name);
+    let mode = match std::env::var("SOURCE_CASSETTE_MODE") {
+        Ok(mode) => mode.parse().unwrap(),
+        Err(_) => CassetteMode::Replay,
+    };
+    if mode == CassetteMode::Replay && !cassette::has_recordings(&dir) {
+        panic!(
+            "no cassettes in {}; record with `SOURCE_CASSETTE_MODE=record cargo test --test {name}` and commit them",
+            dir.display()
+        );
+    }
+    cassette::init(Cassette::new(mode, dir)).unwrap();
+}
@@ -0,0 +1,255 @@
+//! PKCS#11 signer against SoftHSM
+//!
+//! Ignored by default; run with `--ignored`, `SOFTHSM2_MODULE` pointing to `libsofthsm2.so`
+//! and `SOFTHSM2_CONF` pointing to a config with a writable token directory. Each run
+//! initializes a fresh token.
+
+use std::sync::OnceLock;
+
+use alloy::primitives::Address;
+use alloy::{primitives::B256, signers::local::PrivateKeySigner};
+use aqa_publisher::chain::{
+    Network, VoteSigner,
+    pkcs11::{Pkcs11Config, SECP256K1_EC_PARAMS, open_pkcs11_signers},
+    signer_config::{SignerConfig, SignerEntry, SignerSource},
+    signing::{get_signed_vote, recover_vote_signer},
+    types::ActionOptions,
+};
+use cryptoki::context::{CInitializeArgs, CInitializeFlags, Function, Pkcs11};
+use cryptoki::error::{Error, RvError};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, KeyType, ObjectClass};
+use cryptoki::session::UserType;
+use cryptoki::types::AuthPin;
+
+/// Well-known throwaway key (Hardhat account #0)
+const KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+const SO_PIN: &str = "abcdef654321";
+const USER_PIN: &str = "fedcba123456";
+
+/// Serializes the tests: login state is shared by every session on a token, so a test
+/// holding a session would mask another test's wrong-PIN login
+static SERIAL: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Label of the test token, initialized once per run; panics if SoftHSM is not configured
+fn token() -> &'static str {
+    static TOKEN: OnceLock<String> = OnceLock::new();
+    TOKEN.get_or_init(|| {
+        let module = std::env::var("SOFTHSM2_MODULE")
+            .unwrap_or_else(|_| panic!("SOFTHSM2_MODULE not set; the PKCS#11 tests need SoftHSM"));
+        init_token(&module)
+    })
+}
+
+/// Initialize a token holding an imported key (`imported`) and a generated one (`generated`)
+fn init_token(module: &str) -> String {
+    let pkcs11 = Pkcs11::new(module).unwrap();
+    pkcs11
+        .initialize(CInitializeArgs::new(CInitializeFlags::OS_LOCKING_OK))
+        .or_else(|e| match e {
+            Error::Pkcs11(RvError::CryptokiAlreadyInitialized, Function::Initialize) => Ok(()),
+            e => Err(e),
+        })
+        .unwrap();
+
+    // SoftHSM always offers one uninitialized slot
+    let slot = pkcs11
+        .get_slots_with_token()
+        .unwrap()
+        .into_iter()
+        .find(|&slot| !pkcs11.get_token_info(slot).unwrap().token_initialized())
+        .unwrap();
+    let label = format!("aqa-test-{}", std::process::id());
+    let so_pin = AuthPin::new(SO_PIN.into());
+    pkcs11.init_token(slot, &so_pin, &label).unwrap();
+
+    let session = pkcs11.open_rw_session(slot).unwrap();
+    session.login(UserType::So, Some(&so_pin)).unwrap();
+    session.init_pin(&AuthPin::new(USER_PIN.into())).unwrap();
+    session.logout().unwrap();
+    session
+        .login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))
+        .unwrap();
+
+    // Import a known key
+    let wallet: PrivateKeySigner = KEY.parse().unwrap();
+    let point = wallet
+        .credential()
+        .verifying_key()
+        .to_encoded_point(false)
+        .as_bytes()
+        .to_vec();
+    session
+        .create_object(&[
+            Attribute::Class(ObjectClass::PRIVATE_KEY),
+            Attribute::KeyType(KeyType::EC),
+            Attribute::EcParams(SECP256K1_EC_PARAMS.to_vec()),
+            Attribute::Value(wallet.to_bytes().to_vec()),
+            Attribute::Token(true),
+            Attribute::Private(true),
+            Attribute::Sensitive(true),
+            Attribute::Sign(true),
+            Attribute::Label(b"imported".to_vec()),
+        ])
+        .unwrap();
+    session
+        .create_object(&[
+            Attribute::Class(ObjectClass::PUBLIC_KEY),
+            Attribute::KeyType(KeyType::EC),
+            Attribute::EcParams(SECP256K1_EC_PARAMS.to_vec()),
+            Attribute::EcPoint([&[0x04, 0x41][..], &point].concat()),
+            Attribute::Token(true),
+            Attribute::Verify(true),
+            Attribute::Label(b"imported".to_vec()),
+        ])
+        .unwrap();
+
+    // Generate a key that never leaves the token
+    session
+        .generate_key_pair(
+            &Mechanism::EccKeyPairGen,
+            &[
+                Attribute::EcParams(SECP256K1_EC_PARAMS.to_vec()),
+                Attribute::Token(true),
+                Attribute::Verify(true),
+                Attribute::Label(b"generated".to_vec()),
+            ],
+            &[
+                Attribute::Token(true),
+                Attribute::Private(true),
+                Attribute::Sensitive(true),
+                Attribute::Sign(true),
+                Attribute::Label(b"generated".to_vec()),
+            ],
+        )
+        .unwrap();
+    label
+}
+
+fn config(token: &str, keys: &str, pin: &str) -> Pkcs11Config {
+    let module = std::env::var("SOFTHSM2_MODULE").unwrap();
+    Pkcs11Config::parse(&module, Some(token), Some(keys), pin.to_string()).unwrap()
+}
+
+#[tokio::test]
+#[ignore = "needs SoftHSM (SOFTHSM2_MODULE and SOFTHSM2_CONF)"]
+async fn signs_votes_with_token_keys() {
+    let _serial = SERIAL.lock().await;
+    let token = token();
+    let signers = open_pkcs11_signers(&config(token, "imported, generated", USER_PIN)).unwrap();
+    let wallet: PrivateKeySigner = KEY.parse().unwrap();
+    assert_eq!(signers[0].label(), Some("imported"));
+    assert_eq!(signers[0].address(), wallet.address());
+
+    let (network, options) = (Network::Mainnet, ActionOptions::default());
+    for (nonce, signer) in (1_700_000_000_000..).zip(&signers) {
+        let (action, signature) = get_signed_vote(signer, &network, nonce, "0.035", &options)
+            .await
+            .unwrap();
+        assert!(signature.normalize_s().is_none(), "high-s signature");
+        assert_eq!(
+            recover_vote_signer(&action, nonce, &options, &network, &signature).unwrap(),
+            signer.address()
+        );
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs SoftHSM (SOFTHSM2_MODULE and SOFTHSM2_CONF)"]
+async fn signs_concurrently() {
+    let _serial = SERIAL.lock().await;
+    let token = token();
+    let signers = open_pkcs11_signers(&config(token, "generated", USER_PIN)).unwrap();
+    let signer = std::sync::Arc::new(signers.into_iter().next().unwrap());
+
+    let tasks: Vec<_> = (0..8u8)
+        .map(|i| {
+            let signer = signer.clone();
+            tokio::spawn(async move {
+                let hash = B256::repeat_byte(i);
+                let signature = signer.sign_hash(hash).await.unwrap();
+                assert_eq!(
+                    signature.recover_address_from_prehash(&hash).unwrap(),
+                    signer.address()
+                );
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs SoftHSM (SOFTHSM2_MODULE and SOFTHSM2_CONF)"]
+async fn rejects_bad_config() {
+    let _serial = SERIAL.lock().await;
+    let token = token();
+    let err = open_pkcs11_signers(&config(token, "imported", "wrong-pin")).unwrap_err();
+    assert!(err.to_string().contains("log in"), "{err:#}");
+
+    let err = open_pkcs11_signers(&config(token, "missing", USER_PIN)).unwrap_err();
+    assert!(format!("{err:#}").contains("no EC"), "{err:#}");
+
+    let err = open_pkcs11_signers(&config("no-such-token", "imported", USER_PIN)).unwrap_err();
+    assert!(err.to_string().contains("No PKCS#11 token"), "{err:#}");
+}
+
+/// Config file entry for `key_label` on the test token
+fn entry(label: &str, token: &str, key_label: &str, pin_file: &std::path::Path) -> SignerEntry {
+    SignerEntry {
+        label: label.to_string(),
+        validator: Address::repeat_byte(0x01),
+        networks: vec!["mainnet".to_string()],
+        enabled: true,
+        source: SignerSource::Pkcs11 {
+            module: std::env::var("SOFTHSM2_MODULE").unwrap().into(),
+            token_label: token.to_string(),
+            key_label: key_label.to_string(),
+            pin_file: pin_file.to_path_buf(),
+        },
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs SoftHSM (SOFTHSM2_MODULE and SOFTHSM2_CONF)"]
+async fn config_entries_share_one_token_session() {
+    let _serial = SERIAL.lock().await;
+    let token = token();
+    let dir = std::env::temp_dir().join(format!("aqa-pkcs11-config-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let pin_file = dir.join("pin");
+    std::fs::write(&pin_file, format!("{USER_PIN}\n")).unwrap();
+
+    let config = SignerConfig::new(vec![
+        entry("validator-a", token, "imported", &pin_file),
+        entry("validator-b", token, "generated", &pin_file),
+    ])
+    .unwrap();
+    let signers = config.load(&Network::Mainnet).unwrap();
+    let wallet: PrivateKeySigner = KEY.parse().unwrap();
+    assert_eq!(signers.len(), 2);
+    assert_eq!(signers[0].label(), Some("validator-a"));
+    assert_eq!(signers[0].address(), wallet.address());
+    for signer in &signers {
+        let hash = B256::repeat_byte(7);
+        let signature = signer.sign_hash(hash).await.unwrap();
+        assert_eq!(
+            signature.recover_address_from_prehash(&hash).unwrap(),
+            signer.address()
+        );
+    }
+
+    // Same token with a different PIN is rejected rather than silently reusing the session
+    let other_pin = dir.join("other-pin");
+    std::fs::write(&other_pin, "wrong-pin\n").unwrap();
+    let config = SignerConfig::new(vec![
+        entry("validator-a", token, "imported", &pin_file),
+        entry("validator-b", token, "generated", &other_pin),
+    ])
+    .unwrap();
+    let err = config.load(&Network::Mainnet).err().unwrap();
+    assert!(format!("{err:#}").contains("different PINs"), "{err:#}");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,91 @@
+use aqa_publisher::sources::{compute_compounded_average, nyfed::NYFed};
+use chrono::{Days, NaiveDate};
+use std::fs;
+use std::path::PathBuf;
+
+/// Raw NY Fed overnight SOFR `search.csv` response
+const OVERNIGHT_FIXTURE: &str = "tests/fixtures/nyfed/sofr.csv";
+
+/// Raw NY Fed SOFR Averages `search.csv` response covering the same range
+const AVERAGES_FIXTURE: &str = "tests/fixtures/nyfed/sofrai.csv";
+
+/// Last date covered by the fixtures, fixed so re-recording reproduces the same range
+const END_DATE: &str = "2025-09-30";
+
+fn fixture(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(path)
+}
+
+/// Read a checked-in fixture, failing the test if it has not been recorded
+fn read_fixture(path: &str) -> Vec<u8> {
+    fs::read(fixture(path)).unwrap_or_else(|e| {
+        panic!(
+            "missing NY Fed fixture {path} ({e}); record with \
+             `cargo test --test published_averages -- --ignored` and commit it"
+        )
+    })
+}
+
+/// Every published 30-Day Average SOFR in the fixtures must be reproduced exactly
+#[test]
+fn computed_matches_published_exactly() {
+    let overnight = read_fixture(OVERNIGHT_FIXTURE);
+    let averages = read_fixture(AVERAGES_FIXTURE);
+
+    let overnight_rates = NYFed::parse_overnight_rates(&overnight).unwrap();
+    let published = NYFed::parse_averages(&averages).unwrap();
+
+    // Only check dates with a full 30 days of overnight history behind them
+    let first_overnight = *overnight_rates.keys().next().unwrap();
+    let earliest = first_overnight.checked_add_days(Days::new(31)).unwrap();
+
+    let mut checked = 0;
+    let mut mismatches = Vec::new();
+    for (date, published_avg) in published.range(earliest..) {
+        let computed = compute_compounded_average(*date, &overnight_rates).unwrap();
+        if computed != *published_avg {
+            mismatches.push(format!(
+                "{date}: computed {computed}, published {published_avg}"
+            ));
+        }
+        checked += 1;
+    }
+
+    println!("Checked {checked} published 30-day averages");
+    assert!(checked > 0, "fixtures do not cover a full 30-day period");
+    assert!(
+        mismatches.is_empty(),
+        "{} mismatches:\n{}",
+        mismatches.len(),
+        mismatches.join("\n")
+    );
+}
+
+/// Record two years of NY Fed overnight and published average data into fixtures
+/// Requires network access; run manually to refresh the checked-in fixtures
+#[test]
+#[ignore]
+fn record_published_fixtures() {
+    let end: NaiveDate = END_DATE.parse().unwrap();
+    let start = end.checked_sub_days(Days::new(730)).unwrap();
+    let overnight_url = format!(
+        "https://markets.newyorkfed.org/api/rates/secured/sofr/search.csv?startDate={start}&endDate={end}"
+    );
+    let averages_url = format!(
+        "https://markets.newyorkfed.org/api/rates/secured/sofrai/search.csv?type=rate&startDate={start}&endDate={end}"
+    );
+
+    for (url, path) in [
+        (overnight_url, OVERNIGHT_FIXTURE),
+        (averages_url, AVERAGES_FIXTURE),
+    ] {
+        let body = reqwest::blocking::get(&url)
+            .and_then(|resp| resp.error_for_status())
+            .and_then(|resp| resp.bytes())
+            .unwrap();
+        let path = fixture(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, &body).unwrap();
+        println!("Recorded {} bytes to {}", body.len(), path.display());
+    }
+}
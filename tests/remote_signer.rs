@@ -0,0 +1,158 @@
+use std::path::PathBuf;
+
+use alloy::{
+    hex,
+    primitives::{Address, B256},
+    signers::local::PrivateKeySigner,
+};
+use alloy_signer::SignerSync;
+use aqa_publisher::chain::{
+    Network, RemoteSigner, VoteSigner,
+    signer::RemoteEndpoint,
+    signing::{get_signed_vote, recover_vote_signer},
+    types::ActionOptions,
+};
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, UnixListener};
+
+/// Well-known throwaway keys (Hardhat accounts #0 and #1)
+const KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+const OTHER_KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+fn wallet(key: &str) -> PrivateKeySigner {
+    key.parse().unwrap()
+}
+
+/// Stand-in signing service: signs with `key` for requests addressed to `serves`
+async fn handle<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: S,
+    key: PrivateKeySigner,
+    serves: Address,
+) {
+    let mut reader = BufReader::new(stream);
+
+    // Request line and headers
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await.unwrap();
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).await.unwrap();
+        if line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().unwrap();
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await.unwrap();
+
+    // `POST /api/v1/eth1/sign/<address>` with `{"data": "0x..."}`
+    let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let requested: Option<Address> = path
+        .strip_prefix("/api/v1/eth1/sign/")
+        .and_then(|address| address.parse().ok());
+    let (status, response) = if requested == Some(serves) {
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        let hash: B256 = body["data"].as_str().unwrap().parse().unwrap();
+        let signature = key.sign_hash_sync(&hash).unwrap();
+        ("200 OK", hex::encode_prefixed(signature.as_bytes()))
+    } else {
+        ("404 Not Found", "unknown key".to_string())
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+        response.len()
+    );
+    let stream = reader.get_mut();
+    stream.write_all(response.as_bytes()).await.unwrap();
+    stream.shutdown().await.unwrap();
+}
+
+/// Serve over TCP, returning the base URL
+async fn serve_http(key: PrivateKeySigner, serves: Address) -> RemoteEndpoint {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::spawn(handle(stream, key.clone(), serves));
+        }
+    });
+    RemoteEndpoint::parse(&url).unwrap()
+}
+
+/// Serve over a Unix socket, returning its endpoint
+async fn serve_unix(key: PrivateKeySigner, serves: Address, name: &str) -> RemoteEndpoint {
+    let path: PathBuf =
+        std::env::temp_dir().join(format!("aqa-signer-{}-{name}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::spawn(handle(stream, key.clone(), serves));
+        }
+    });
+    RemoteEndpoint::parse(&format!("unix:{}", path.display())).unwrap()
+}
+
+/// A remote vote is identical to one signed with the local key (RFC 6979 signatures)
+async fn assert_signs_like_local(remote: &RemoteSigner) {
+    let local = wallet(KEY);
+    let (network, nonce, rate) = (Network::Mainnet, 1_700_000_000_000, "0.03515319");
+    let options = ActionOptions::default();
+
+    let (action, signature) = get_signed_vote(remote, &network, nonce, rate, &options)
+        .await
+        .unwrap();
+    let (_, expected) = get_signed_vote(&local, &network, nonce, rate, &options)
+        .await
+        .unwrap();
+    assert_eq!(signature, expected);
+    assert_eq!(
+        recover_vote_signer(&action, nonce, &options, &network, &signature).unwrap(),
+        local.address()
+    );
+}
+
+#[tokio::test]
+async fn signs_over_http() {
+    let address = wallet(KEY).address();
+    let endpoint = serve_http(wallet(KEY), address).await;
+    assert_signs_like_local(&RemoteSigner::new(address, endpoint)).await;
+}
+
+#[tokio::test]
+async fn signs_over_unix_socket() {
+    let address = wallet(KEY).address();
+    let endpoint = serve_unix(wallet(KEY), address, "ok").await;
+    assert_signs_like_local(&RemoteSigner::new(address, endpoint)).await;
+}
+
+#[tokio::test]
+async fn rejects_signature_from_wrong_key() {
+    // Service answers for our address but signs with another key
+    let address = wallet(KEY).address();
+    let endpoint = serve_unix(wallet(OTHER_KEY), address, "wrong-key").await;
+    let err = RemoteSigner::new(address, endpoint)
+        .sign_hash(B256::repeat_byte(1))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("expected"), "{err}");
+}
+
+#[tokio::test]
+async fn reports_unknown_address() {
+    let endpoint = serve_http(wallet(KEY), wallet(KEY).address()).await;
+    let err = RemoteSigner::new(wallet(OTHER_KEY).address(), endpoint)
+        .sign_hash(B256::repeat_byte(1))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("HTTP 404"), "{err}");
+}
@@ -1,13 +1,16 @@
 use aqa_publisher::sources::{Source, fred::Fred, nyfed::NYFed, ofr::OFR};
-use chrono::{Days, Local};
+use chrono::Days;
 use rayon::prelude::*;
 use std::sync::Mutex;
 use std::thread;
 use std::time::Duration;
 
+mod common;
+
 #[test]
 fn compare_sources_over_two_years() {
-    let end_date = Local::now().date_naive();
+    common::use_cassette("source_comparison");
+    let end_date = common::end_date();
     let start_date = end_date.checked_sub_days(Days::new(365)).unwrap();
 
     // Collect dates every 3 days
@@ -20,9 +23,9 @@ fn compare_sources_over_two_years() {
 
     // Define all sources to test
     let sources: Vec<(String, Box<dyn Source + Sync>)> = vec![
-        ("FRED".to_string(), Box::new(Fred::default())),
-        ("NYFed".to_string(), Box::new(NYFed::default())),
-        ("OFR".to_string(), Box::new(OFR::default())),
+        ("FRED".to_string(), Box::new(Fred)),
+        ("NYFed".to_string(), Box::new(NYFed)),
+        ("OFR".to_string(), Box::new(OFR)),
     ];
 
     println!(
@@ -52,7 +55,7 @@ fn compare_sources_over_two_years() {
                         errors
                             .lock()
                             .unwrap()
-                            .push(format!("Date {}: {} failed: {}", date, name, e));
+                            .push(format!("Date {date}: {name} failed: {e}"));
                     }
                 }
             }
@@ -71,14 +74,12 @@ fn compare_sources_over_two_years() {
                     for j in (i + 1)..results.len() {
                         let (name1, date1, val1) = &results[i];
                         let (name2, date2, val2) = &results[j];
-                        let diff = (*val1 as i64 - *val2 as i64).abs() as u64;
+                        let diff = (*val1 as i64 - *val2 as i64).unsigned_abs();
 
                         if diff > max_diff {
                             max_diff = diff;
-                            diff_info = format!(
-                                "{} on {} vs {} on {} (diff: {})",
-                                name1, date1, name2, date2, diff
-                            );
+                            diff_info =
+                                format!("{name1} on {date1} vs {name2} on {date2} (diff: {diff})");
                         }
                     }
                 }
@@ -89,7 +90,7 @@ fn compare_sources_over_two_years() {
                     discrepancies
                         .lock()
                         .unwrap()
-                        .push(format!("Date {}: large discrepancy - {}", date, diff_info));
+                        .push(format!("Date {date}: large discrepancy - {diff_info}"));
                 }
             }
         });
@@ -105,15 +106,14 @@ fn compare_sources_over_two_years() {
 
     // Report results
     println!(
-        "Comparison complete: {}/{} dates had all sources succeed",
-        success_count,
+        "Comparison complete: {success_count}/{} dates had all sources succeed",
         dates.len()
     );
 
     if !errors.is_empty() {
         println!("\n{} source errors found:", errors.len());
         for (i, error) in errors.iter().take(10).enumerate() {
-            println!("  {}. {}", i + 1, error);
+            println!("  {}. {error}", i + 1);
         }
         if errors.len() > 10 {
             println!("  ... and {} more errors", errors.len() - 10);
@@ -123,7 +123,7 @@ fn compare_sources_over_two_years() {
     if !discrepancies.is_empty() {
         println!("\n{} large discrepancies found:", discrepancies.len());
         for (i, disc) in discrepancies.iter().take(10).enumerate() {
-            println!("  {}. {}", i + 1, disc);
+            println!("  {}. {disc}", i + 1);
         }
         if discrepancies.len() > 10 {
             println!("  ... and {} more discrepancies", discrepancies.len() - 10);